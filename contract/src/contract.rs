@@ -1,14 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{State, STATE};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::state::{Config, State, CONFIG, STATE};
 
+pub mod access;
+pub mod escrow;
 pub mod execute;
+pub mod fees;
+pub mod halt;
+pub mod merkle;
+pub mod metadata;
+pub mod migrate;
+pub mod order_book;
+pub mod positions;
 pub mod query;
+pub mod tickers;
+
+use format as f;
 
 // Denomination of the token we're using
 pub const DENOM: &str = "uhuahua";
@@ -22,19 +35,54 @@ pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let state = State {
-        owner: info.sender.clone(),
+        owner: Some(info.sender.clone()),
+        pending_owner: None,
+    };
+
+    let total_shares = msg.total_shares.unwrap_or(execute::stocks::TOTAL_SHARES);
+    let auction_duration = msg
+        .auction_duration
+        .unwrap_or(execute::stocks::AUCTION_DURATION);
+
+    let config = Config {
+        denom: msg.denom.unwrap_or(DENOM.to_string()),
+        total_shares,
+        min_total_shares: msg.min_total_shares.unwrap_or(total_shares),
+        max_total_shares: msg.max_total_shares.unwrap_or(total_shares),
+        auction_duration,
+        min_auction_duration: msg.min_auction_duration.unwrap_or(auction_duration),
+        max_auction_duration: msg.max_auction_duration.unwrap_or(auction_duration),
+        min_bid_increment: msg
+            .min_bid_increment
+            .unwrap_or(query::bids::MIN_BID_INCREMENT),
+        soft_close_window: msg.soft_close_window.unwrap_or(0),
+        soft_close_extension: msg.soft_close_extension.unwrap_or(0),
+        max_auction_extension: msg.max_auction_extension.unwrap_or(0),
+        bid_lock_window: msg.bid_lock_window.unwrap_or(0),
+        fee_bps: msg.fee_bps.unwrap_or(0),
+        fee_collector: msg
+            .fee_collector
+            .map(|collector| deps.api.addr_validate(&collector))
+            .transpose()?,
+        keeper_reward_bps: msg.keeper_reward_bps.unwrap_or(0),
+        max_royalty_bps: msg.max_royalty_bps.unwrap_or(0),
+        unrevealed_slash_bps: msg.unrevealed_slash_bps.unwrap_or(0),
     };
 
+    execute::config::validate_config(&config)?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     STATE.save(deps.storage, &state)?;
+    CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
-        .add_attribute("owner", info.sender))
+        .add_attribute("owner", info.sender)
+        .add_attribute("denom", config.denom))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -45,13 +93,44 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::CreateStock { ticker } => {
-            execute::stocks::create_stock(deps, env, info, ticker)
-        }
+        ExecuteMsg::CreateStock {
+            ticker,
+            total_shares,
+            metadata,
+        } => execute::stocks::create_stock(deps, env, info, ticker, total_shares, metadata),
 
-        ExecuteMsg::StartAuction { stock_id } => {
-            execute::stocks::start_auction(deps, env, info, stock_id)
-        }
+        ExecuteMsg::StartAuction {
+            stock_id,
+            start_at,
+            duration,
+            reserve_price,
+            uniform_price,
+            dutch_auction,
+            sealed_bid,
+            funding_goal,
+            max_shares_per_address,
+            min_shares_per_bid,
+            presale,
+            retained_allocation,
+        } => execute::stocks::start_auction(
+            deps,
+            env,
+            info,
+            stock_id,
+            execute::stocks::AuctionParams {
+                start_at,
+                duration,
+                reserve_price,
+                uniform_price,
+                dutch_auction,
+                sealed_bid,
+                funding_goal,
+                max_shares_per_address,
+                min_shares_per_bid,
+                presale,
+                retained_allocation,
+            },
+        ),
 
         ExecuteMsg::EndAuction { stock_id } => {
             execute::stocks::end_auction(deps, env, info, stock_id)
@@ -63,6 +142,52 @@ pub fn execute(
             shares,
         } => execute::bids::place_bid(deps, env, info, stock_id, price_per_share, shares),
 
+        ExecuteMsg::PlacePresaleBid {
+            stock_id,
+            price_per_share,
+            shares,
+            allocation,
+            proof,
+        } => execute::bids::place_presale_bid(
+            deps,
+            env,
+            info,
+            stock_id,
+            price_per_share,
+            shares,
+            execute::bids::PresaleProof { allocation, proof },
+        ),
+
+        ExecuteMsg::CancelBid { bid_id } => execute::bids::cancel_bid(deps, env, info, bid_id),
+
+        ExecuteMsg::IncreaseBid {
+            bid_id,
+            price_per_share,
+            additional_shares,
+        } => {
+            execute::bids::increase_bid(deps, env, info, bid_id, price_per_share, additional_shares)
+        }
+
+        ExecuteMsg::CommitBid {
+            stock_id,
+            commitment,
+        } => execute::sealed::commit_bid(deps, env, info, stock_id, commitment),
+
+        ExecuteMsg::RevealBid {
+            bid_id,
+            price_per_share,
+            shares,
+            salt,
+        } => execute::sealed::reveal_bid(deps, env, info, bid_id, price_per_share, shares, salt),
+
+        ExecuteMsg::ClaimVested { stock_id } => {
+            execute::vesting::claim_vested(deps, env, info, stock_id)
+        }
+
+        ExecuteMsg::BuyAuctionShares { stock_id, shares } => {
+            execute::dutch::buy_auction_shares(deps, env, info, stock_id, shares)
+        }
+
         ExecuteMsg::CreateBuyOrder {
             stock_id,
             price_per_share,
@@ -103,6 +228,65 @@ pub fn execute(
             price_per_share,
             slippage,
         ),
+
+        ExecuteMsg::UpdateConfig(msg) => execute::config::update_config(deps, env, info, msg),
+
+        ExecuteMsg::ProposeNewOwner { new_owner } => {
+            execute::ownership::propose_new_owner(deps, env, info, new_owner)
+        }
+
+        ExecuteMsg::AcceptOwnership {} => execute::ownership::accept_ownership(deps, env, info),
+
+        ExecuteMsg::RenounceOwnership {} => execute::ownership::renounce_ownership(deps, env, info),
+
+        ExecuteMsg::GrantRole { role, address } => {
+            execute::ownership::grant_role(deps, env, info, role, address)
+        }
+
+        ExecuteMsg::RevokeRole { role, address } => {
+            execute::ownership::revoke_role(deps, env, info, role, address)
+        }
+
+        ExecuteMsg::UpdateFeeConfig {
+            fee_bps,
+            fee_collector,
+        } => execute::fees::update_fee_config(deps, env, info, fee_bps, fee_collector),
+
+        ExecuteMsg::WithdrawFees {} => execute::fees::withdraw_fees(deps, env, info),
+
+        ExecuteMsg::SetRoyalty {
+            stock_id,
+            royalty_bps,
+            payout_address,
+        } => {
+            execute::royalties::set_royalty(deps, env, info, stock_id, royalty_bps, payout_address)
+        }
+
+        ExecuteMsg::Halt { stock_id, reason } => {
+            execute::halt::halt(deps, env, info, stock_id, reason)
+        }
+
+        ExecuteMsg::Resume { stock_id } => execute::halt::resume(deps, env, info, stock_id),
+
+        ExecuteMsg::SettleAuction { stock_id, limit } => {
+            execute::settlement::settle_auction(deps, env, info, stock_id, limit)
+        }
+
+        ExecuteMsg::DelistStock { stock_id } => {
+            execute::stocks::delist_stock(deps, env, info, stock_id)
+        }
+
+        ExecuteMsg::ReserveTicker { ticker, holder } => {
+            execute::tickers::reserve_ticker(deps, env, info, ticker, holder)
+        }
+
+        ExecuteMsg::ReleaseTicker { ticker } => {
+            execute::tickers::release_ticker(deps, env, info, ticker)
+        }
+
+        ExecuteMsg::UpdateStockMetadata { stock_id, metadata } => {
+            execute::stocks::update_stock_metadata(deps, env, info, stock_id, metadata)
+        }
     }
 }
 
@@ -118,6 +302,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             in_auction,
             in_sale,
             marked_as_active_auction,
+            scheduled,
         } => to_json_binary(&query::stocks::get_all_stocks(
             deps,
             env,
@@ -125,6 +310,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             in_auction,
             in_sale,
             marked_as_active_auction,
+            scheduled,
         )?),
 
         QueryMsg::GetStocksByInfluencer {
@@ -153,11 +339,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetMinimumBidPrice {
             stock_id,
             shares_requested,
+            bidder,
         } => to_json_binary(&query::bids::get_minimum_bid_price(
             deps,
             env,
             stock_id,
             shares_requested,
+            bidder,
         )?),
 
         QueryMsg::GetOpenBidsByStock { stock_id } => {
@@ -168,8 +356,8 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&query::bids::get_bids_by_stock_id(deps, env, stock_id)?)
         }
 
-        QueryMsg::GetShareById { share_id } => {
-            to_json_binary(&query::shares::get_shares_by_id(deps, env, share_id)?)
+        QueryMsg::GetShare { stock_id, owner } => {
+            to_json_binary(&query::shares::get_share(deps, env, stock_id, owner)?)
         }
 
         QueryMsg::GetSharesByOwner { owner } => {
@@ -243,5 +431,127 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetSalesById { sale_id } => {
             to_json_binary(&query::sales::get_sale_by_id(deps, env, sale_id)?)
         }
+
+        QueryMsg::GetEscrowBalance {} => {
+            to_json_binary(&query::escrow::get_escrow_balance(deps, env)?)
+        }
+
+        QueryMsg::GetBuyOrderEscrow { buy_order_id } => to_json_binary(
+            &query::escrow::get_buy_order_escrow(deps, env, buy_order_id)?,
+        ),
+
+        QueryMsg::GetConfig {} => to_json_binary(&query::config::get_config(deps, env)?),
+
+        QueryMsg::GetOwnership {} => to_json_binary(&query::ownership::get_ownership(deps, env)?),
+
+        QueryMsg::GetRoleMembers { role } => {
+            to_json_binary(&query::ownership::get_role_members(deps, env, role)?)
+        }
+
+        QueryMsg::GetHaltStatus { stock_id } => {
+            to_json_binary(&query::halt::get_halt_status(deps, env, stock_id)?)
+        }
+
+        QueryMsg::GetAccruedFees {} => to_json_binary(&query::fees::get_accrued_fees(deps, env)?),
+
+        QueryMsg::GetDutchAuction { stock_id } => {
+            to_json_binary(&query::dutch::get_dutch_auction(deps, env, stock_id)?)
+        }
+
+        QueryMsg::GetSealedBid { bid_id } => {
+            to_json_binary(&query::bids::get_sealed_bid(deps, env, bid_id)?)
+        }
+
+        QueryMsg::GetVesting { stock_id } => {
+            to_json_binary(&query::vesting::get_vesting(deps, env, stock_id)?)
+        }
+
+        QueryMsg::GetRoyalty { stock_id } => {
+            to_json_binary(&query::royalties::get_royalty(deps, env, stock_id)?)
+        }
+
+        QueryMsg::GetStockByTicker { ticker } => {
+            to_json_binary(&query::tickers::get_stock_by_ticker(deps, env, ticker)?)
+        }
+
+        QueryMsg::GetReservedTickers {} => {
+            to_json_binary(&query::tickers::get_reserved_tickers(deps, env)?)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::GenericError(f!(
+            "Cannot migrate from {} to {CONTRACT_NAME}",
+            stored.contract
+        )));
+    }
+
+    let from_version = parse_version(&stored.version)?;
+    let to_version = parse_version(CONTRACT_VERSION)?;
+
+    if from_version > to_version {
+        return Err(ContractError::GenericError(f!(
+            "Cannot migrate from {from_version} to older version {to_version}"
+        )));
+    }
+
+    let mut res = Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", from_version.to_string())
+        .add_attribute("to_version", to_version.to_string());
+
+    // Steps run in order, each only when the stored state predates it
+    if from_version < Version::new(0, 2, 0) {
+        // Bid funds are escrowed from now on
+        migrate::ensure_no_running_auctions(deps.storage)?;
+
+        // Merge the per-bid share records into one position per (stock, owner)
+        let merged = migrate::merge_share_records(deps.storage)?;
+
+        // Lock the shares listed in open sell orders, before the orders are indexed
+        // so the ones that get cancelled stay out of the order book
+        let current_time = env.block.time.nanos() / 1_000_000;
+        let (locked, capped) = migrate::lock_open_sell_orders(deps.storage, current_time)?;
+
+        // Index the open orders by price
+        let indexed = migrate::index_open_orders(deps.storage)?;
+
+        // Track the funds already held for open buy orders
+        let backfilled = migrate::backfill_buy_order_escrows(deps.storage)?;
+
+        // Turn Bid.open from 0/1 into a bool
+        let converted = migrate::convert_bid_open_flags(deps.storage)?;
+
+        // Register the tickers of existing stocks
+        let tickers = migrate::index_tickers(deps.storage)?;
+
+        // Contracts instantiated before the config existed run on the defaults
+        if CONFIG.may_load(deps.storage)?.is_none() {
+            CONFIG.save(deps.storage, &migrate::default_config())?;
+        }
+
+        res = res
+            .add_attribute("merged_share_records", merged.to_string())
+            .add_attribute("locked_sell_orders", locked.to_string())
+            .add_attribute("capped_sell_orders", capped.to_string())
+            .add_attribute("indexed_open_orders", indexed.to_string())
+            .add_attribute("backfilled_escrows", backfilled.to_string())
+            .add_attribute("converted_bids", converted.to_string())
+            .add_attribute("indexed_tickers", tickers.to_string());
     }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(res)
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    version
+        .parse()
+        .map_err(|_| ContractError::GenericError(f!("Invalid contract version {version}")))
 }
@@ -0,0 +1,33 @@
+use cosmwasm_std::{Addr, StdResult, Storage};
+
+use crate::{
+    state::{Role, ROLES, STATE},
+    ContractError,
+};
+
+pub fn is_owner(storage: &dyn Storage, addr: &Addr) -> StdResult<bool> {
+    let state = STATE.load(storage)?;
+
+    Ok(state.owner.as_ref() == Some(addr))
+}
+
+// The owner passes every role check
+pub fn has_role(storage: &dyn Storage, role: Role, addr: &Addr) -> StdResult<bool> {
+    Ok(is_owner(storage, addr)? || ROLES.has(storage, (role.as_str(), addr)))
+}
+
+pub fn ensure_owner(storage: &dyn Storage, addr: &Addr) -> Result<(), ContractError> {
+    if !is_owner(storage, addr)? {
+        return Err(ContractError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+pub fn ensure_role(storage: &dyn Storage, role: Role, addr: &Addr) -> Result<(), ContractError> {
+    if !has_role(storage, role, addr)? {
+        return Err(ContractError::Unauthorized);
+    }
+
+    Ok(())
+}
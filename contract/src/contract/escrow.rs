@@ -0,0 +1,139 @@
+use cosmwasm_std::Storage;
+use cw_storage_plus::Map;
+
+use crate::{
+    state::{AUCTION_ESCROWS, BUY_ORDER_ESCROWS, TOTAL_ESCROW},
+    ContractError,
+};
+
+use format as f;
+
+fn deposit(
+    storage: &mut dyn Storage,
+    escrows: &Map<&[u8], u128>,
+    key: &[u8],
+    amount: u128,
+) -> Result<(), ContractError> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let held = escrows.may_load(storage, key)?.unwrap_or(0);
+    escrows.save(storage, key, &(held + amount))?;
+
+    let total = TOTAL_ESCROW.may_load(storage)?.unwrap_or(0);
+    TOTAL_ESCROW.save(storage, &(total + amount))?;
+
+    Ok(())
+}
+
+// `holder` names the escrow entry in the error message
+fn withdraw(
+    storage: &mut dyn Storage,
+    escrows: &Map<&[u8], u128>,
+    key: &[u8],
+    amount: u128,
+    holder: impl FnOnce() -> String,
+) -> Result<(), ContractError> {
+    let held = escrows.may_load(storage, key)?.unwrap_or(0);
+
+    if held < amount {
+        return Err(ContractError::GenericError(f!(
+            "{} holds {held} in escrow, cannot withdraw {amount}",
+            holder()
+        )));
+    }
+
+    // drop the entry once it holds nothing
+    if held == amount {
+        escrows.remove(storage, key);
+    } else {
+        escrows.save(storage, key, &(held - amount))?;
+    }
+
+    let total = TOTAL_ESCROW.may_load(storage)?.unwrap_or(0);
+    TOTAL_ESCROW.save(storage, &(total - amount))?;
+
+    Ok(())
+}
+
+// Record funds held for a buy order
+pub fn deposit_buy_order_escrow(
+    storage: &mut dyn Storage,
+    buy_order_id: u64,
+    amount: u128,
+) -> Result<(), ContractError> {
+    deposit(
+        storage,
+        &BUY_ORDER_ESCROWS,
+        &buy_order_id.to_be_bytes(),
+        amount,
+    )
+}
+
+// Take funds out of a buy order's escrow (e.g. to pay a seller)
+pub fn withdraw_buy_order_escrow(
+    storage: &mut dyn Storage,
+    buy_order_id: u64,
+    amount: u128,
+) -> Result<(), ContractError> {
+    withdraw(
+        storage,
+        &BUY_ORDER_ESCROWS,
+        &buy_order_id.to_be_bytes(),
+        amount,
+        || f!("Buy order {buy_order_id}"),
+    )
+}
+
+// Empty a buy order's escrow, returning the amount it held
+pub fn release_buy_order_escrow(
+    storage: &mut dyn Storage,
+    buy_order_id: u64,
+) -> Result<u128, ContractError> {
+    let held = BUY_ORDER_ESCROWS
+        .may_load(storage, &buy_order_id.to_be_bytes())?
+        .unwrap_or(0);
+
+    withdraw_buy_order_escrow(storage, buy_order_id, held)?;
+
+    Ok(held)
+}
+
+// Record funds held for a stock's auction
+pub fn deposit_auction_escrow(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+    amount: u128,
+) -> Result<(), ContractError> {
+    deposit(storage, &AUCTION_ESCROWS, &stock_id.to_be_bytes(), amount)
+}
+
+// Take funds out of an auction's escrow (e.g. to pay a keeper)
+pub fn withdraw_auction_escrow(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+    amount: u128,
+) -> Result<(), ContractError> {
+    withdraw(
+        storage,
+        &AUCTION_ESCROWS,
+        &stock_id.to_be_bytes(),
+        amount,
+        || f!("Auction of stock {stock_id}"),
+    )
+}
+
+// Empty an auction's escrow, returning the amount it held
+pub fn release_auction_escrow(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+) -> Result<u128, ContractError> {
+    let held = AUCTION_ESCROWS
+        .may_load(storage, &stock_id.to_be_bytes())?
+        .unwrap_or(0);
+
+    withdraw_auction_escrow(storage, stock_id, held)?;
+
+    Ok(held)
+}
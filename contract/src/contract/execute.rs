@@ -1,3 +1,13 @@
 pub mod bids;
+pub mod config;
+pub mod dutch;
+pub mod fees;
+pub mod halt;
 pub mod orders;
+pub mod ownership;
+pub mod royalties;
+pub mod sealed;
+pub mod settlement;
 pub mod stocks;
+pub mod tickers;
+pub mod vesting;
@@ -1,13 +1,24 @@
-use cosmwasm_std::{coins, Addr, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{coins, Addr, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response, Storage};
 
 use crate::{
-    contract::{query, DENOM},
-    state::{Bid, BIDS, BID_COUNT, STOCKS},
+    contract::{
+        escrow::{deposit_auction_escrow, withdraw_auction_escrow},
+        halt::ensure_not_halted,
+        merkle::{presale_leaf, verify_proof},
+        query,
+    },
+    state::{Bid, Config, Stock, BIDS, BID_COUNT, CONFIG, STOCKS},
     ContractError,
 };
 
 use format as f;
 
+// Proof that a presale bidder is on the stock's allow list
+pub struct PresaleProof {
+    pub allocation: u64,
+    pub proof: Vec<String>,
+}
+
 pub fn place_bid(
     deps: DepsMut,
     env: Env,
@@ -16,11 +27,46 @@ pub fn place_bid(
     price_per_share: u128,
     shares: u64,
 ) -> Result<Response, ContractError> {
+    submit_bid(deps, env, info, stock_id, price_per_share, shares, None)
+}
+
+pub fn place_presale_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+    price_per_share: u128,
+    shares: u64,
+    presale_proof: PresaleProof,
+) -> Result<Response, ContractError> {
+    submit_bid(
+        deps,
+        env,
+        info,
+        stock_id,
+        price_per_share,
+        shares,
+        Some(presale_proof),
+    )
+}
+
+fn submit_bid(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+    price_per_share: u128,
+    shares: u64,
+    presale_proof: Option<PresaleProof>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_not_halted(deps.storage, stock_id)?;
+
     // Check if funds are sent
-    let sent_funds = info.funds.iter().find(|coin| coin.denom == DENOM);
+    let sent_funds = info.funds.iter().find(|coin| coin.denom == config.denom);
 
     if sent_funds.is_none() {
-        return Err(ContractError::GenericError(f!("No {DENOM} sent")));
+        return Err(ContractError::GenericError(f!("No {} sent", config.denom)));
     }
 
     let sent_amount = sent_funds.unwrap().amount.u128();
@@ -35,7 +81,7 @@ pub fn place_bid(
 
     // Load the stock
     let stock_id_bytes = stock_id.to_be_bytes();
-    let stock = STOCKS
+    let mut stock = STOCKS
         .load(deps.storage, &stock_id_bytes)
         .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
 
@@ -47,6 +93,27 @@ pub fn place_bid(
         }
     }
 
+    // Check the auction isn't being settled
+    if stock.settling {
+        return Err(ContractError::GenericError(
+            "Auction is being settled".to_string(),
+        ));
+    }
+
+    // Dutch auctions sell at their current price instead of taking bids
+    if stock.dutch_auction.is_some() {
+        return Err(ContractError::GenericError(f!(
+            "Stock {stock_id} is sold in a Dutch auction, buy its shares instead"
+        )));
+    }
+
+    // Sealed bid auctions only take commitments
+    if stock.sealed_bid.is_some() {
+        return Err(ContractError::GenericError(f!(
+            "Stock {stock_id} takes sealed bids, commit one instead"
+        )));
+    }
+
     // Check if auction hasn't started
     if stock.auction_start.is_none() {
         return Err(ContractError::GenericError(f!(
@@ -54,9 +121,76 @@ pub fn place_bid(
         )));
     }
 
+    // Check if auction is scheduled for later
+    if let Some(start_timestamp) = stock.auction_start {
+        if current_time < start_timestamp {
+            return Err(ContractError::GenericError(f!(
+                "Auction starts at {start_timestamp}"
+            )));
+        }
+    }
+
+    // Until the public phase only allow-listed addresses can bid, each up to its allocation
+    let in_presale = stock
+        .presale
+        .as_ref()
+        .is_some_and(|presale| current_time < presale.public_start);
+
+    match (&stock.presale, presale_proof) {
+        (Some(presale), Some(presale_proof)) if in_presale => {
+            let leaf = presale_leaf(&info.sender, presale_proof.allocation);
+
+            if !verify_proof(&presale.merkle_root, leaf, &presale_proof.proof)? {
+                return Err(ContractError::GenericError(f!(
+                    "Bidder is not on the presale allow list of stock {stock_id}"
+                )));
+            }
+
+            let held_shares = query::bids::held_shares(deps.storage, &stock, &info.sender)?;
+            let allocation = presale_proof.allocation.saturating_sub(held_shares);
+
+            if shares > allocation {
+                return Err(ContractError::GenericError(f!(
+                    "Bidder can only bid for {allocation} more shares in the presale"
+                )));
+            }
+        }
+        (Some(presale), None) if in_presale => {
+            return Err(ContractError::GenericError(f!(
+                "Stock {stock_id} is in presale until {}, place a presale bid",
+                presale.public_start
+            )));
+        }
+        (_, Some(_)) => {
+            return Err(ContractError::GenericError(f!(
+                "Stock {stock_id} is not in presale"
+            )));
+        }
+        _ => {}
+    }
+
+    // Check the bid meets the reserve price
+    if price_per_share < stock.reserve_price {
+        return Err(ContractError::GenericError(f!(
+            "Bid price is below the reserve price of {}",
+            stock.reserve_price
+        )));
+    }
+
+    // Check the bid is within the stock's allocation caps
+    if let Some(min_shares) = stock.min_shares_per_bid {
+        if shares < min_shares {
+            return Err(ContractError::GenericError(f!(
+                "Bids must be for at least {min_shares} shares"
+            )));
+        }
+    }
+
+    ensure_allocation(deps.storage, &stock, &info.sender, shares)?;
+
     // Get the current minimum bid price
     let min_bid_price =
-        query::bids::get_minimum_bid_price(deps.as_ref(), env.clone(), stock_id, shares)?
+        query::bids::get_minimum_bid_price(deps.as_ref(), env.clone(), stock_id, shares, None)?
             .min_price
             .parse()
             .map_err(|_| ContractError::GenericError(f!("Price conversion error")))?;
@@ -80,15 +214,18 @@ pub fn place_bid(
         shares_requested: shares,
         remaining_shares: shares,
         created_at: current_time,
-        open: 1,
+        open: true,
         active: true,
     };
 
     // Save the bid
     BIDS.save(deps.storage, &bid_id.to_be_bytes(), &bid)?;
 
-    // amount to send to influencer wallet
-    let mut influencer_pay = expected_amount;
+    // Hold the bid's funds until the auction ends
+    deposit_auction_escrow(deps.storage, stock_id, expected_amount)?;
+    stock.amount_raised += expected_amount;
+
+    let (auction_end, extended) = apply_soft_close(&config, &mut stock, current_time);
 
     // messages for funds disbursement
     let mut messages = vec![];
@@ -104,33 +241,27 @@ pub fn place_bid(
     if excess_amount > 0 {
         messages.push(CosmosMsg::Bank(BankMsg::Send {
             to_address: info.sender.to_string(),
-            amount: coins(excess_amount, DENOM),
+            amount: coins(excess_amount, &config.denom),
         }));
     }
 
     // Process outbidding
-    let outbids = process_outbids(deps, env, bid_id, stock_id, shares)?;
+    let outbids = process_outbids(deps.branch(), bid_id, stock_id, shares)?;
 
-    // Refund outbids
+    // Refund outbids out of the auction's escrow
     for outbid in outbids {
         if outbid.1 > 0 {
-            // subtract refund from influencer's pay
-            influencer_pay -= outbid.1;
+            withdraw_auction_escrow(deps.storage, stock_id, outbid.1)?;
+            stock.amount_raised -= outbid.1;
 
             messages.push(CosmosMsg::Bank(BankMsg::Send {
                 to_address: outbid.0.to_string(),
-                amount: coins(outbid.1, DENOM),
+                amount: coins(outbid.1, &config.denom),
             }));
         }
     }
 
-    // Transfer the influencer's pay to influencer
-    if influencer_pay > 0 {
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: stock.influencer.to_string(),
-            amount: coins(influencer_pay, DENOM),
-        }));
-    }
+    STOCKS.save(deps.storage, &stock_id_bytes, &stock)?;
 
     Ok(Response::new()
         .add_attribute("action", "place_bid")
@@ -143,30 +274,320 @@ pub fn place_bid(
             "total_amount",
             (price_per_share * shares as u128).to_string(),
         )
+        .add_attribute("auction_end", auction_end.to_string())
+        .add_attribute("auction_extended", extended.to_string())
+        .add_attribute("presale", in_presale.to_string())
         .add_messages(messages))
 }
 
-fn process_outbids(
+// Check the bidder's open bids stay within the stock's per-address cap with `shares` more
+pub fn ensure_allocation(
+    storage: &dyn Storage,
+    stock: &Stock,
+    bidder: &Addr,
+    shares: u64,
+) -> Result<(), ContractError> {
+    if let Some(allocation) = query::bids::remaining_allocation(storage, stock, bidder)? {
+        if shares > allocation {
+            return Err(ContractError::GenericError(f!(
+                "Bidder can only bid for {allocation} more shares of stock {}",
+                stock.id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// A bid close to the end pushes the end out so outbid bidders can respond,
+// up to the auction's hard end. Returns the auction's end and whether it moved,
+// the caller saves the stock.
+fn apply_soft_close(config: &Config, stock: &mut Stock, current_time: u64) -> (u64, bool) {
+    let auction_end = stock.auction_end.unwrap_or(current_time);
+
+    if config.soft_close_window == 0 || auction_end - current_time > config.soft_close_window {
+        return (auction_end, false);
+    }
+
+    let max_end = stock.max_auction_end.unwrap_or(auction_end);
+    let new_end = (auction_end + config.soft_close_extension).min(max_end);
+
+    if new_end <= auction_end {
+        return (auction_end, false);
+    }
+
+    stock.auction_end = Some(new_end);
+
+    (new_end, true)
+}
+
+// Load one of the sender's bids that is still open in a running auction, along with its stock
+fn load_open_bid(
+    storage: &dyn Storage,
+    sender: &Addr,
+    bid_id: u64,
+    current_time: u64,
+) -> Result<(Bid, Stock), ContractError> {
+    let bid = BIDS
+        .load(storage, &bid_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Bid with id {bid_id}")))?;
+
+    if bid.bidder != *sender {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let stock = STOCKS
+        .load(storage, &bid.stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {}", bid.stock_id)))?;
+
+    // Dutch purchases and sealed bids are final
+    if stock.dutch_auction.is_some() || stock.sealed_bid.is_some() {
+        return Err(ContractError::GenericError(f!(
+            "Bids on stock {} can't be changed",
+            stock.id
+        )));
+    }
+
+    if query::bids::opening_bid(storage, &stock)?.is_some_and(|opening| opening.id == bid_id) {
+        return Err(ContractError::GenericError(
+            "The opening bid can't be changed".to_string(),
+        ));
+    }
+
+    if stock.settling || stock.auction_end.is_some_and(|end| current_time > end) {
+        return Err(ContractError::GenericError("Auction has ended".to_string()));
+    }
+
+    if !bid.open || bid.remaining_shares == 0 {
+        return Err(ContractError::GenericError(f!(
+            "Bid {bid_id} is no longer open"
+        )));
+    }
+
+    Ok((bid, stock))
+}
+
+pub fn cancel_bid(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
+    bid_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let current_time = env.block.time.nanos() / 1_000_000;
+
+    let (mut bid, mut stock) = load_open_bid(deps.storage, &info.sender, bid_id, current_time)?;
+
+    let auction_end = stock.auction_end.unwrap_or(current_time);
+
+    if auction_end - current_time < config.bid_lock_window {
+        return Err(ContractError::GenericError(f!(
+            "Bids can't be cancelled within {} milliseconds of the auction's end",
+            config.bid_lock_window
+        )));
+    }
+
+    // The shares go back to the influencer's opening bid so the open bids still cover the supply
+    let mut opening_bid = query::bids::opening_bid(deps.storage, &stock)?
+        .ok_or_else(|| ContractError::NotFound(f!("Opening bid of stock {}", stock.id)))?;
+
+    opening_bid.remaining_shares += bid.remaining_shares;
+    opening_bid.open = true;
+    BIDS.save(deps.storage, &opening_bid.id.to_be_bytes(), &opening_bid)?;
+
+    let refund = bid.price_per_share * bid.remaining_shares as u128;
+    let cancelled_shares = bid.remaining_shares;
+
+    bid.remaining_shares = 0;
+    bid.open = false;
+    BIDS.save(deps.storage, &bid_id.to_be_bytes(), &bid)?;
+
+    withdraw_auction_escrow(deps.storage, stock.id, refund)?;
+
+    stock.amount_raised -= refund;
+    STOCKS.save(deps.storage, &stock.id.to_be_bytes(), &stock)?;
+
+    let mut messages = vec![];
+
+    if refund > 0 {
+        messages.push(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(refund, &config.denom),
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_bid")
+        .add_attribute("bid_id", bid_id.to_string())
+        .add_attribute("stock_id", stock.id.to_string())
+        .add_attribute("bidder", info.sender.to_string())
+        .add_attribute("cancelled_shares", cancelled_shares.to_string())
+        .add_attribute("refund", refund.to_string())
+        .add_messages(messages))
+}
+
+pub fn increase_bid(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bid_id: u64,
+    price_per_share: Option<u128>,
+    additional_shares: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let current_time = env.block.time.nanos() / 1_000_000;
+
+    let (mut bid, mut stock) = load_open_bid(deps.storage, &info.sender, bid_id, current_time)?;
+
+    // Bids can still be cancelled during a halt, but not raised
+    ensure_not_halted(deps.storage, stock.id)?;
+
+    let price_per_share = price_per_share.unwrap_or(bid.price_per_share);
+    let additional_shares = additional_shares.unwrap_or(0);
+
+    if price_per_share < bid.price_per_share {
+        return Err(ContractError::GenericError(
+            "Bid price can't be lowered".to_string(),
+        ));
+    }
+
+    if price_per_share == bid.price_per_share && additional_shares == 0 {
+        return Err(ContractError::GenericError(
+            "Nothing to increase".to_string(),
+        ));
+    }
+
+    if additional_shares > 0 {
+        // Presale bids are held to the allocation they were proven for
+        if let Some(presale) = &stock.presale {
+            if current_time < presale.public_start {
+                return Err(ContractError::GenericError(f!(
+                    "Shares can't be added to bids until {}",
+                    presale.public_start
+                )));
+            }
+        }
+
+        if bid.remaining_shares + additional_shares > stock.auctioned_shares() {
+            return Err(ContractError::GenericError(f!(
+                "Cannot bid for more than the {} shares of stock {}",
+                stock.auctioned_shares(),
+                stock.id
+            )));
+        }
+
+        ensure_allocation(deps.storage, &stock, &info.sender, additional_shares)?;
+
+        // The added shares are outbid from others like a new bid's, never from this one
+        let min_bid_price =
+            query::bids::outbid_price(deps.storage, &stock, additional_shares, Some(bid_id))?;
+
+        if price_per_share < min_bid_price {
+            return Err(ContractError::GenericError(f!(
+                "Bid price too low. Minimum price is {min_bid_price}"
+            )));
+        }
+    }
+
+    let new_shares = bid.remaining_shares + additional_shares;
+    let held_amount = bid.price_per_share * bid.remaining_shares as u128;
+    let expected_amount = price_per_share * new_shares as u128 - held_amount;
+
+    let sent_amount = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == config.denom)
+        .map_or(0, |coin| coin.amount.u128());
+
+    if sent_amount < expected_amount {
+        return Err(ContractError::GenericError(f!(
+            "Insufficient funds sent. Expected {expected_amount}, got {sent_amount}"
+        )));
+    }
+
+    let mut messages = vec![];
+
+    if additional_shares > 0 {
+        let outbids = process_outbids(deps.branch(), bid_id, stock.id, additional_shares)?;
+
+        // Refund outbids out of the auction's escrow
+        for (bidder, amount) in outbids {
+            if amount > 0 {
+                withdraw_auction_escrow(deps.storage, stock.id, amount)?;
+                stock.amount_raised -= amount;
+
+                messages.push(BankMsg::Send {
+                    to_address: bidder.to_string(),
+                    amount: coins(amount, &config.denom),
+                });
+            }
+        }
+    }
+
+    bid.price_per_share = price_per_share;
+    bid.shares_requested += additional_shares;
+    bid.remaining_shares = new_shares;
+    BIDS.save(deps.storage, &bid_id.to_be_bytes(), &bid)?;
+
+    deposit_auction_escrow(deps.storage, stock.id, expected_amount)?;
+    stock.amount_raised += expected_amount;
+
+    let (auction_end, extended) = apply_soft_close(&config, &mut stock, current_time);
+    STOCKS.save(deps.storage, &stock.id.to_be_bytes(), &stock)?;
+
+    // Send back whatever was sent above the difference
+    if sent_amount > expected_amount {
+        messages.push(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(sent_amount - expected_amount, &config.denom),
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "increase_bid")
+        .add_attribute("bid_id", bid_id.to_string())
+        .add_attribute("stock_id", stock.id.to_string())
+        .add_attribute("bidder", info.sender.to_string())
+        .add_attribute("price_per_share", price_per_share.to_string())
+        .add_attribute("shares", new_shares.to_string())
+        .add_attribute("charged", expected_amount.to_string())
+        .add_attribute("auction_end", auction_end.to_string())
+        .add_attribute("auction_extended", extended.to_string())
+        .add_messages(messages))
+}
+
+fn process_outbids(
+    deps: DepsMut,
     bid_id: u64,
     stock_id: u64,
     shares_requested: u64,
 ) -> Result<Vec<(Addr, u128)>, ContractError> {
     let mut available_shares = shares_requested;
 
-    // Get all open bids for this stock, ordered by price (ascending)
-    let open_bids = query::bids::get_open_bids_by_stock(deps.as_ref(), env, stock_id)?.bids;
+    // Read the open bids from the lowest price up, only as many as the new bid outbids
+    let mut outbid_bids = vec![];
+    let mut covered_shares = 0;
 
-    let mut outbids = Vec::new();
+    for bid in query::bids::open_bids(deps.storage, stock_id) {
+        let bid = bid?;
 
-    // Start with loweset priced bids and work up
-    for mut bid in open_bids {
         // Exclude the newly created bid
         if bid.id == bid_id {
             continue;
         }
 
+        covered_shares += bid.remaining_shares;
+        outbid_bids.push(bid);
+
+        if covered_shares >= shares_requested {
+            break;
+        }
+    }
+
+    let mut outbids = Vec::new();
+
+    // Start with loweset priced bids and work up
+    for mut bid in outbid_bids {
         if available_shares <= bid.remaining_shares {
             // calculate remaining_shares and add to outbids
             let remaining_shares = bid.remaining_shares - available_shares;
@@ -176,8 +597,9 @@ fn process_outbids(
                 bid.price_per_share * available_shares as u128,
             ));
 
-            // update remaining_shares
+            // update remaining_shares, closing the bid if nothing is left
             bid.remaining_shares = remaining_shares;
+            bid.open = remaining_shares > 0;
             BIDS.save(deps.storage, &bid.id.to_be_bytes(), &bid)?;
 
             break;
@@ -195,7 +617,7 @@ fn process_outbids(
             bid.remaining_shares = 0;
 
             // close bid
-            bid.open = 0;
+            bid.open = false;
 
             // save
             BIDS.save(deps.storage, &bid.id.to_be_bytes(), &bid)?;
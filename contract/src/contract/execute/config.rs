@@ -0,0 +1,202 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    contract::{access::ensure_owner, fees::MAX_FEE_BPS},
+    msg::UpdateConfigMsg,
+    state::{Config, ACCRUED_FEES, CONFIG, TOTAL_ESCROW},
+    ContractError,
+};
+
+use format as f;
+
+pub fn validate_config(config: &Config) -> Result<(), ContractError> {
+    if config.denom.is_empty() {
+        return Err(ContractError::GenericError(
+            "Denom cannot be empty".to_string(),
+        ));
+    }
+
+    if config.total_shares == 0 {
+        return Err(ContractError::GenericError(
+            "Total shares must be greater than 0".to_string(),
+        ));
+    }
+
+    if config.min_total_shares == 0 {
+        return Err(ContractError::GenericError(
+            "Minimum total shares must be greater than 0".to_string(),
+        ));
+    }
+
+    if config.total_shares < config.min_total_shares
+        || config.total_shares > config.max_total_shares
+    {
+        return Err(ContractError::GenericError(f!(
+            "Total shares must be between {} and {}",
+            config.min_total_shares,
+            config.max_total_shares
+        )));
+    }
+
+    if config.auction_duration == 0 {
+        return Err(ContractError::GenericError(
+            "Auction duration must be greater than 0".to_string(),
+        ));
+    }
+
+    if config.auction_duration < config.min_auction_duration
+        || config.auction_duration > config.max_auction_duration
+    {
+        return Err(ContractError::GenericError(f!(
+            "Auction duration must be between {} and {} milliseconds",
+            config.min_auction_duration,
+            config.max_auction_duration
+        )));
+    }
+
+    if config.min_bid_increment == 0 {
+        return Err(ContractError::GenericError(
+            "Minimum bid increment must be greater than 0".to_string(),
+        ));
+    }
+
+    // Fee and royalty together can't take more than the whole sale
+    if config.fee_bps as u32 + config.max_royalty_bps as u32 > MAX_FEE_BPS as u32 {
+        return Err(ContractError::GenericError(f!(
+            "Fee and maximum royalty cannot exceed {MAX_FEE_BPS} basis points together"
+        )));
+    }
+
+    // Fee and keeper reward both come out of auction proceeds
+    if config.fee_bps as u32 + config.keeper_reward_bps as u32 > MAX_FEE_BPS as u32 {
+        return Err(ContractError::GenericError(f!(
+            "Fee and keeper reward cannot exceed {MAX_FEE_BPS} basis points together"
+        )));
+    }
+
+    if config.unrevealed_slash_bps > MAX_FEE_BPS {
+        return Err(ContractError::GenericError(f!(
+            "Unrevealed bid slash cannot exceed {MAX_FEE_BPS} basis points"
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(denom) = msg.denom {
+        // Escrowed funds and accrued fees are in the current denom
+        let held = TOTAL_ESCROW.may_load(deps.storage)?.unwrap_or(0)
+            + ACCRUED_FEES.may_load(deps.storage)?.unwrap_or(0);
+
+        if denom != config.denom && held > 0 {
+            return Err(ContractError::GenericError(
+                "Cannot change denom while funds are held in escrow or as fees".to_string(),
+            ));
+        }
+
+        config.denom = denom;
+    }
+
+    if let Some(total_shares) = msg.total_shares {
+        config.total_shares = total_shares;
+    }
+
+    if let Some(min_total_shares) = msg.min_total_shares {
+        config.min_total_shares = min_total_shares;
+    }
+
+    if let Some(max_total_shares) = msg.max_total_shares {
+        config.max_total_shares = max_total_shares;
+    }
+
+    if let Some(auction_duration) = msg.auction_duration {
+        config.auction_duration = auction_duration;
+    }
+
+    if let Some(min_auction_duration) = msg.min_auction_duration {
+        config.min_auction_duration = min_auction_duration;
+    }
+
+    if let Some(max_auction_duration) = msg.max_auction_duration {
+        config.max_auction_duration = max_auction_duration;
+    }
+
+    if let Some(min_bid_increment) = msg.min_bid_increment {
+        config.min_bid_increment = min_bid_increment;
+    }
+
+    if let Some(soft_close_window) = msg.soft_close_window {
+        config.soft_close_window = soft_close_window;
+    }
+
+    if let Some(soft_close_extension) = msg.soft_close_extension {
+        config.soft_close_extension = soft_close_extension;
+    }
+
+    if let Some(max_auction_extension) = msg.max_auction_extension {
+        config.max_auction_extension = max_auction_extension;
+    }
+
+    if let Some(bid_lock_window) = msg.bid_lock_window {
+        config.bid_lock_window = bid_lock_window;
+    }
+
+    if let Some(keeper_reward_bps) = msg.keeper_reward_bps {
+        config.keeper_reward_bps = keeper_reward_bps;
+    }
+
+    if let Some(max_royalty_bps) = msg.max_royalty_bps {
+        config.max_royalty_bps = max_royalty_bps;
+    }
+
+    if let Some(unrevealed_slash_bps) = msg.unrevealed_slash_bps {
+        config.unrevealed_slash_bps = unrevealed_slash_bps;
+    }
+
+    validate_config(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("denom", config.denom)
+        .add_attribute("total_shares", config.total_shares.to_string())
+        .add_attribute("min_total_shares", config.min_total_shares.to_string())
+        .add_attribute("max_total_shares", config.max_total_shares.to_string())
+        .add_attribute("auction_duration", config.auction_duration.to_string())
+        .add_attribute(
+            "min_auction_duration",
+            config.min_auction_duration.to_string(),
+        )
+        .add_attribute(
+            "max_auction_duration",
+            config.max_auction_duration.to_string(),
+        )
+        .add_attribute("min_bid_increment", config.min_bid_increment.to_string())
+        .add_attribute("soft_close_window", config.soft_close_window.to_string())
+        .add_attribute(
+            "soft_close_extension",
+            config.soft_close_extension.to_string(),
+        )
+        .add_attribute(
+            "max_auction_extension",
+            config.max_auction_extension.to_string(),
+        )
+        .add_attribute("bid_lock_window", config.bid_lock_window.to_string())
+        .add_attribute("keeper_reward_bps", config.keeper_reward_bps.to_string())
+        .add_attribute("max_royalty_bps", config.max_royalty_bps.to_string())
+        .add_attribute(
+            "unrevealed_slash_bps",
+            config.unrevealed_slash_bps.to_string(),
+        ))
+}
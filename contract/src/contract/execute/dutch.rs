@@ -0,0 +1,183 @@
+use cosmwasm_std::{coins, BankMsg, DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    contract::{
+        escrow::deposit_auction_escrow,
+        execute::bids::ensure_allocation,
+        fees::{calculate_fee, take_protocol_fee},
+        halt::ensure_not_halted,
+        positions::credit_shares,
+        query,
+    },
+    state::{DutchAuction, Sale, BIDS, CONFIG, SALES, SALE_COUNT, STOCKS},
+    ContractError,
+};
+
+use format as f;
+
+// Buy shares in a Dutch auction at the current price.
+// The shares are taken out of the influencer's opening bid and sold right away,
+// settlement only returns the ones that weren't sold.
+pub fn buy_auction_shares(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+    shares: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_not_halted(deps.storage, stock_id)?;
+
+    if shares == 0 {
+        return Err(ContractError::GenericError(
+            "Cannot buy 0 shares".to_string(),
+        ));
+    }
+
+    let mut stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
+
+    let (Some(dutch_auction), Some(auction_start)) = (&stock.dutch_auction, stock.auction_start)
+    else {
+        return Err(ContractError::GenericError(f!(
+            "Stock {stock_id} is not sold in a Dutch auction"
+        )));
+    };
+
+    let current_time = env.block.time.nanos() / 1_000_000;
+
+    if current_time < auction_start {
+        return Err(ContractError::GenericError(f!(
+            "Auction starts at {auction_start}"
+        )));
+    }
+
+    if stock.settling || stock.auction_end.is_some_and(|end| current_time > end) {
+        return Err(ContractError::GenericError("Auction has ended".to_string()));
+    }
+
+    let mut opening_bid = query::bids::opening_bid(deps.storage, &stock)?
+        .ok_or_else(|| ContractError::NotFound(f!("Opening bid of stock {stock_id}")))?;
+
+    if shares > opening_bid.remaining_shares {
+        return Err(ContractError::GenericError(f!(
+            "Only {} shares are left",
+            opening_bid.remaining_shares
+        )));
+    }
+
+    if let Some(min_shares) = stock.min_shares_per_bid {
+        if shares < min_shares {
+            return Err(ContractError::GenericError(f!(
+                "Purchases must be for at least {min_shares} shares"
+            )));
+        }
+    }
+
+    ensure_allocation(deps.storage, &stock, &info.sender, shares)?;
+
+    let price_per_share = dutch_auction.price_at(auction_start, current_time);
+    let cost = price_per_share * shares as u128;
+
+    let sent_amount = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == config.denom)
+        .map_or(0, |coin| coin.amount.u128());
+
+    if sent_amount < cost {
+        return Err(ContractError::GenericError(f!(
+            "Insufficient funds sent. Expected {cost}, got {sent_amount}"
+        )));
+    }
+
+    // The shares come out of what the influencer still holds
+    opening_bid.remaining_shares -= shares;
+    opening_bid.open = opening_bid.remaining_shares > 0;
+    BIDS.save(deps.storage, &opening_bid.id.to_be_bytes(), &opening_bid)?;
+
+    credit_shares(deps.storage, stock_id, &info.sender, shares)?;
+
+    stock.amount_raised += cost;
+    STOCKS.save(deps.storage, &stock_id.to_be_bytes(), &stock)?;
+
+    // The protocol fee comes out of the influencer's pay
+    let fee = take_protocol_fee(deps.storage, &config, cost)?;
+
+    // Create Sale record
+    let sale_id = SALE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
+    SALE_COUNT.save(deps.storage, &sale_id)?;
+
+    let sale = Sale {
+        id: sale_id,
+        stock_id,
+        no_of_shares: shares,
+        price_per_share,
+        from: stock.influencer.clone(),
+        to: info.sender.clone(),
+        created_at: current_time,
+        fee,
+        // Auction sales are primary sales, no royalty
+        royalty: 0,
+    };
+
+    SALES.save(deps.storage, &sale_id.to_be_bytes(), &sale)?;
+
+    // Hold back the reward of whoever settles the auction
+    let keeper_reward = calculate_fee(cost, config.keeper_reward_bps);
+    deposit_auction_escrow(deps.storage, stock_id, keeper_reward)?;
+
+    let influencer_pay = cost - fee - keeper_reward;
+
+    let mut messages = vec![];
+
+    if influencer_pay > 0 {
+        messages.push(BankMsg::Send {
+            to_address: stock.influencer.to_string(),
+            amount: coins(influencer_pay, &config.denom),
+        });
+    }
+
+    // Send back whatever was sent above the price
+    if sent_amount > cost {
+        messages.push(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(sent_amount - cost, &config.denom),
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "buy_auction_shares")
+        .add_attribute("sale_id", sale_id.to_string())
+        .add_attribute("stock_id", stock_id.to_string())
+        .add_attribute("buyer", info.sender.to_string())
+        .add_attribute("price_per_share", price_per_share.to_string())
+        .add_attribute("shares", shares.to_string())
+        .add_attribute("total_amount", cost.to_string())
+        .add_attribute("fee", fee.to_string())
+        .add_attribute("remaining_shares", opening_bid.remaining_shares.to_string())
+        .add_messages(messages))
+}
+
+pub fn validate_dutch_auction(dutch_auction: &DutchAuction) -> Result<(), ContractError> {
+    if dutch_auction.start_price == 0 {
+        return Err(ContractError::GenericError(
+            "Start price must be greater than 0".to_string(),
+        ));
+    }
+
+    if dutch_auction.floor_price > dutch_auction.start_price {
+        return Err(ContractError::GenericError(
+            "Floor price cannot be above the start price".to_string(),
+        ));
+    }
+
+    if dutch_auction.decrement_interval == 0 {
+        return Err(ContractError::GenericError(
+            "Decrement interval must be greater than 0".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use cosmwasm_std::{coins, BankMsg, DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    contract::{
+        access::{ensure_owner, ensure_role},
+        execute::config::validate_config,
+    },
+    state::{Role, ACCRUED_FEES, CONFIG},
+    ContractError,
+};
+
+pub fn update_fee_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    fee_bps: Option<u16>,
+    fee_collector: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::FeeManager, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(fee_bps) = fee_bps {
+        config.fee_bps = fee_bps;
+    }
+
+    if let Some(fee_collector) = fee_collector {
+        config.fee_collector = Some(deps.api.addr_validate(&fee_collector)?);
+    }
+
+    validate_config(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_fee_config")
+        .add_attribute("fee_bps", config.fee_bps.to_string())
+        .add_attribute(
+            "fee_collector",
+            config
+                .fee_collector
+                .map_or("none".to_string(), |collector| collector.to_string()),
+        ))
+}
+
+// Send every accrued fee to the fee collector
+pub fn withdraw_fees(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let fee_collector = config.fee_collector.ok_or(ContractError::GenericError(
+        "No fee collector configured".to_string(),
+    ))?;
+
+    let accrued = ACCRUED_FEES.may_load(deps.storage)?.unwrap_or(0);
+
+    if accrued == 0 {
+        return Err(ContractError::GenericError(
+            "No fees to withdraw".to_string(),
+        ));
+    }
+
+    ACCRUED_FEES.save(deps.storage, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_fees")
+        .add_attribute("amount", accrued.to_string())
+        .add_attribute("fee_collector", fee_collector.to_string())
+        .add_message(BankMsg::Send {
+            to_address: fee_collector.to_string(),
+            amount: coins(accrued, config.denom),
+        }))
+}
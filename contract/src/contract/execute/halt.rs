@@ -0,0 +1,100 @@
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response};
+
+use crate::{
+    contract::access::ensure_role,
+    state::{HaltInfo, Role, GLOBAL_HALT, STOCKS, STOCK_HALTS},
+    ContractError,
+};
+
+use format as f;
+
+// Halt all trading, or a single stock's when stock_id is set
+pub fn halt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: Option<u64>,
+    reason: String,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Operator, &info.sender)?;
+
+    if reason.trim().is_empty() {
+        return Err(ContractError::GenericError(
+            "A reason is required to halt trading".to_string(),
+        ));
+    }
+
+    let halt = HaltInfo {
+        reason: reason.clone(),
+        halted_at: env.block.time.nanos() / 1_000_000,
+        halted_by: info.sender.clone(),
+    };
+
+    let mut event = Event::new("halt")
+        .add_attribute("reason", reason)
+        .add_attribute("halted_by", info.sender);
+
+    match stock_id {
+        Some(stock_id) => {
+            if !STOCKS.has(deps.storage, &stock_id.to_be_bytes()) {
+                return Err(ContractError::NotFound(f!("Stock with id {stock_id}")));
+            }
+
+            STOCK_HALTS.save(deps.storage, &stock_id.to_be_bytes(), &halt)?;
+            event = event
+                .add_attribute("scope", "stock")
+                .add_attribute("stock_id", stock_id.to_string());
+        }
+        None => {
+            GLOBAL_HALT.save(deps.storage, &halt)?;
+            event = event.add_attribute("scope", "global");
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "halt")
+        .add_event(event))
+}
+
+// Lift a global halt, or a single stock's when stock_id is set
+pub fn resume(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    stock_id: Option<u64>,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Operator, &info.sender)?;
+
+    let mut event = Event::new("resume").add_attribute("resumed_by", info.sender);
+
+    match stock_id {
+        Some(stock_id) => {
+            let key = stock_id.to_be_bytes();
+
+            if !STOCK_HALTS.has(deps.storage, &key) {
+                return Err(ContractError::GenericError(f!(
+                    "Trading of stock {stock_id} is not halted"
+                )));
+            }
+
+            STOCK_HALTS.remove(deps.storage, &key);
+            event = event
+                .add_attribute("scope", "stock")
+                .add_attribute("stock_id", stock_id.to_string());
+        }
+        None => {
+            if !GLOBAL_HALT.exists(deps.storage) {
+                return Err(ContractError::GenericError(
+                    "Trading is not halted".to_string(),
+                ));
+            }
+
+            GLOBAL_HALT.remove(deps.storage);
+            event = event.add_attribute("scope", "global");
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "resume")
+        .add_event(event))
+}
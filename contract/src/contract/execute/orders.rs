@@ -1,10 +1,19 @@
-use std::u128;
-
-use crate::contract::{query, DENOM};
-use crate::msg::OrderSort;
+use crate::contract::escrow::{
+    deposit_buy_order_escrow, release_buy_order_escrow, withdraw_buy_order_escrow,
+};
+use crate::contract::fees::{royalty_recipient, take_protocol_fee, take_royalty};
+use crate::contract::halt::ensure_not_halted;
+use crate::contract::order_book::{
+    add_open_buy_order, add_open_sell_order, best_buy_order, best_sell_order,
+    remove_open_buy_order, remove_open_sell_order,
+};
+use crate::contract::positions::{
+    credit_shares, debit_locked_shares, debit_shares, load_position, lock_shares, unlock_shares,
+};
+use crate::contract::query;
 use crate::state::{
-    BuyOrder, Sale, SellOrder, Share, BUY_ORDERS, BUY_ORDER_COUNT, SALES, SALE_COUNT, SELL_ORDERS,
-    SELL_ORDER_COUNT, SHARES, SHARE_COUNT, STOCKS,
+    BuyOrder, Sale, SellOrder, BUY_ORDERS, BUY_ORDER_COUNT, CONFIG, SALES, SALE_COUNT, SELL_ORDERS,
+    SELL_ORDER_COUNT, STOCKS,
 };
 use crate::ContractError;
 use cosmwasm_std::{coins, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response};
@@ -17,6 +26,8 @@ pub fn cancel_buy_order(
     info: MessageInfo,
     buy_order_id: u64,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
     let mut buy_order =
         query::orders::get_buy_order_by_id(deps.as_ref(), env.clone(), buy_order_id)?.order;
 
@@ -35,8 +46,24 @@ pub fn cancel_buy_order(
     buy_order.resolved_at = Some(current_timestamp);
 
     BUY_ORDERS.save(deps.storage, &buy_order_id.to_be_bytes(), &buy_order)?;
+    remove_open_buy_order(deps.storage, &buy_order);
+
+    // Refund whatever the order still holds for its unfilled shares
+    let refund = release_buy_order_escrow(deps.storage, buy_order_id)?;
 
-    Ok(Response::new().add_attribute("action", "cancel_buy_order"))
+    let mut response = Response::new()
+        .add_attribute("action", "cancel_buy_order")
+        .add_attribute("buy_order_id", buy_order_id.to_string())
+        .add_attribute("refund", refund.to_string());
+
+    if refund > 0 {
+        response = response.add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: buy_order.owner.to_string(),
+            amount: coins(refund, &config.denom),
+        }));
+    }
+
+    Ok(response)
 }
 
 pub fn cancel_sell_order(
@@ -63,8 +90,22 @@ pub fn cancel_sell_order(
     sell_order.resolved_at = Some(current_timestamp);
 
     SELL_ORDERS.save(deps.storage, &sell_order_id.to_be_bytes(), &sell_order)?;
+    remove_open_sell_order(deps.storage, &sell_order);
+
+    // Release the shares the order still had locked
+    let unsold_shares = sell_order.available_shares - sell_order.sold_shares;
 
-    Ok(Response::new().add_attribute("action", "cancel_sell_order"))
+    unlock_shares(
+        deps.storage,
+        sell_order.stock_id,
+        &sell_order.owner,
+        unsold_shares,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_sell_order")
+        .add_attribute("sell_order_id", sell_order_id.to_string())
+        .add_attribute("unlocked_shares", unsold_shares.to_string()))
 }
 
 pub fn create_buy_order(
@@ -75,6 +116,9 @@ pub fn create_buy_order(
     shares: u64,
     price_per_share: u128,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_not_halted(deps.storage, stock_id)?;
+
     // Validate inputs
     if shares == 0 {
         return Err(ContractError::GenericError(f!(
@@ -94,17 +138,18 @@ pub fn create_buy_order(
 
     let current_timestamp = env.block.time.nanos() / 1_000_000;
 
-    if stock.auction_end.is_none() || stock.auction_end > Some(current_timestamp) {
+    // Trading opens once the auction has been settled
+    if !stock.is_settled() {
         return Err(ContractError::GenericError(f!("Stock is not in sale")));
     }
 
     // Ensure the buyer has sent enough funds
     let required_amount = shares as u128 * price_per_share;
 
-    let sent_funds = info.funds.iter().find(|coin| coin.denom == DENOM);
+    let sent_funds = info.funds.iter().find(|coin| coin.denom == config.denom);
 
     if sent_funds.is_none() {
-        return Err(ContractError::GenericError(f!("No {DENOM} sent")));
+        return Err(ContractError::GenericError(f!("No {} sent", config.denom)));
     }
 
     let sent_amount = sent_funds.unwrap().amount.u128();
@@ -115,21 +160,13 @@ pub fn create_buy_order(
         )));
     }
 
-    // Get sell orders to match with
-    let open_sell_orders = query::orders::get_open_sell_orders_by_stock_id(
-        deps.as_ref(),
-        env.clone(),
-        stock_id,
-        OrderSort::PriceAsc,
-    )?
-    .orders;
-
     let mut remaining_shares = shares;
     let mut messages = vec![];
+    let mut total_royalty = 0u128;
     let mut total_cost = 0;
 
-    // match sell orders
-    for mut sell_order in open_sell_orders {
+    // match sell orders, walking the book from the cheapest order up
+    while let Some(mut sell_order) = best_sell_order(deps.storage, stock_id)? {
         if sell_order.price_per_share > price_per_share {
             break;
         }
@@ -142,6 +179,7 @@ pub fn create_buy_order(
 
         if sell_order.sold_shares == sell_order.available_shares {
             sell_order.resolved_at = Some(current_timestamp);
+            remove_open_sell_order(deps.storage, &sell_order);
         }
 
         SELL_ORDERS.save(deps.storage, &sell_order.id.to_be_bytes(), &sell_order)?;
@@ -149,46 +187,15 @@ pub fn create_buy_order(
         let cost = take as u128 * sell_order.price_per_share;
         total_cost += cost;
 
-        // Transfer share from seller to buyer
-        let mut seller_share = query::shares::get_shares_by_owner(
-            deps.as_ref(),
-            env.clone(),
-            sell_order.owner.clone(),
-        )?
-        .shares
-        .into_iter()
-        .filter(|share| share.stock_id == stock_id)
-        .nth(0)
-        .ok_or(ContractError::GenericError("This shouldn't happen".into()))?;
-
-        seller_share.no_of_shares -= take;
-        SHARES.save(deps.storage, &seller_share.id.to_be_bytes(), &seller_share)?;
-
-        let buyer_share =
-            query::shares::get_shares_by_owner(deps.as_ref(), env.clone(), info.sender.clone())?
-                .shares
-                .into_iter()
-                .filter(|share| share.stock_id == stock_id)
-                .nth(0);
-
-        // if buyer already have shares in stock update share count
-        if let Some(mut buyer_share) = buyer_share {
-            buyer_share.no_of_shares += take;
-            SHARES.save(deps.storage, &buyer_share.id.to_be_bytes(), &buyer_share)?;
-        } else {
-            // else create new share for buyer
-            let new_share_id = SHARE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
-            SHARE_COUNT.save(deps.storage, &new_share_id)?;
-
-            let new_share = Share {
-                id: new_share_id,
-                stock_id,
-                no_of_shares: take,
-                owner: info.sender.clone(),
-            };
-
-            SHARES.save(deps.storage, &new_share_id.to_be_bytes(), &new_share)?;
-        }
+        // The protocol fee and royalty come out of the seller's proceeds
+        let fee = take_protocol_fee(deps.storage, &config, cost)?;
+        let royalty = take_royalty(deps.storage, &config, &stock, &sell_order.owner, cost)?;
+        total_royalty += royalty;
+
+        // Transfer shares from seller to buyer,
+        // the sold shares were locked by the sell order
+        debit_locked_shares(deps.storage, stock_id, &sell_order.owner, take)?;
+        credit_shares(deps.storage, stock_id, &info.sender, take)?;
 
         // Create Sale record
         let sale_id = SALE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
@@ -202,15 +209,21 @@ pub fn create_buy_order(
             from: sell_order.owner.clone(),
             to: info.sender.clone(),
             created_at: current_timestamp,
+            fee,
+            royalty,
         };
 
         SALES.save(deps.storage, &sale_id.to_be_bytes(), &sale)?;
 
         // Send funds to seller
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: sell_order.owner.to_string(),
-            amount: coins(cost, DENOM),
-        }));
+        let proceeds = cost - fee - royalty;
+
+        if proceeds > 0 {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: sell_order.owner.to_string(),
+                amount: coins(proceeds, &config.denom),
+            }));
+        }
 
         remaining_shares -= take;
         if remaining_shares == 0 {
@@ -218,6 +231,14 @@ pub fn create_buy_order(
         }
     }
 
+    // Pay the influencer's royalty
+    if total_royalty > 0 {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: royalty_recipient(deps.storage, &stock)?.to_string(),
+            amount: coins(total_royalty, &config.denom),
+        }));
+    }
+
     // Refund excess funds
     let price_of_remaining_shares = remaining_shares as u128 * price_per_share;
 
@@ -226,7 +247,7 @@ pub fn create_buy_order(
     if excess_funds > 0 {
         messages.push(CosmosMsg::Bank(BankMsg::Send {
             to_address: info.sender.to_string(),
-            amount: coins(excess_funds, DENOM),
+            amount: coins(excess_funds, &config.denom),
         }));
     }
 
@@ -260,6 +281,14 @@ pub fn create_buy_order(
     };
 
     BUY_ORDERS.save(deps.storage, &buy_order_id.to_be_bytes(), &buy_order)?;
+
+    if buy_order.resolved_at.is_none() {
+        add_open_buy_order(deps.storage, &buy_order)?;
+    }
+
+    // Hold the funds for the unfilled shares until the order is filled or cancelled
+    deposit_buy_order_escrow(deps.storage, buy_order_id, price_of_remaining_shares)?;
+
     response = response
         .add_attribute("buy_order_id", buy_order_id.to_string())
         .add_attribute("remaining_shares", remaining_shares.to_string());
@@ -275,6 +304,9 @@ pub fn create_sell_order(
     shares: u64,
     price_per_share: u128,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_not_halted(deps.storage, stock_id)?;
+
     // Validate inputs
     if shares == 0 {
         return Err(ContractError::GenericError(f!(
@@ -294,42 +326,27 @@ pub fn create_sell_order(
 
     let current_timestamp = env.block.time.nanos() / 1_000_000;
 
-    if stock.auction_end.is_none() || stock.auction_end > Some(current_timestamp) {
+    // Trading opens once the auction has been settled
+    if !stock.is_settled() {
         return Err(ContractError::GenericError(f!("Stock is not in sale")));
     }
 
     // Check seller has enough shares in this stock
-    let mut seller_share =
-        query::shares::get_shares_by_owner(deps.as_ref(), env.clone(), info.sender.clone())?
-            .shares
-            .into_iter()
-            .filter(|share| share.stock_id == stock_id)
-            .nth(0)
-            .ok_or(ContractError::GenericError(
-                "You do not have shares in this stock".into(),
-            ))?;
-
-    if seller_share.no_of_shares < shares {
+    let seller_share = load_position(deps.storage, stock_id, &info.sender)?;
+
+    if seller_share.available_shares() < shares {
         return Err(ContractError::GenericError(f!(
-            "Insufficient shares. Has {}, needs {shares}",
-            seller_share.no_of_shares
+            "Insufficient shares. Has {} available, needs {shares}",
+            seller_share.available_shares()
         )));
     }
 
     let mut remaining_shares = shares;
     let mut messages = vec![];
+    let mut total_royalty = 0u128;
 
-    // Get buy orders to match with
-    let open_buy_orders = query::orders::get_open_buy_orders_by_stock_id(
-        deps.as_ref(),
-        env.clone(),
-        stock_id,
-        OrderSort::PriceDesc,
-    )?
-    .orders;
-
-    // match buy orders
-    for mut buy_order in open_buy_orders {
+    // match buy orders, walking the book from the highest price down
+    while let Some(mut buy_order) = best_buy_order(deps.storage, stock_id)? {
         if buy_order.price_per_share < price_per_share {
             break;
         }
@@ -342,42 +359,21 @@ pub fn create_sell_order(
 
         if buy_order.bought_shares == buy_order.requested_shares {
             buy_order.resolved_at = Some(current_timestamp);
+            remove_open_buy_order(deps.storage, &buy_order);
         }
 
         BUY_ORDERS.save(deps.storage, &buy_order.id.to_be_bytes(), &buy_order)?;
 
+        let cost = take as u128 * buy_order.price_per_share;
+
+        // The protocol fee and royalty come out of the seller's proceeds
+        let fee = take_protocol_fee(deps.storage, &config, cost)?;
+        let royalty = take_royalty(deps.storage, &config, &stock, &info.sender, cost)?;
+        total_royalty += royalty;
+
         // Transfer shares from seller to buyer
-        seller_share.no_of_shares -= take;
-        SHARES.save(deps.storage, &seller_share.id.to_be_bytes(), &seller_share)?;
-
-        let buyer_share = query::shares::get_shares_by_owner(
-            deps.as_ref(),
-            env.clone(),
-            buy_order.owner.clone(),
-        )?
-        .shares
-        .into_iter()
-        .filter(|share| share.stock_id == stock_id)
-        .nth(0);
-
-        // if buyer already have shares in stock update share count
-        if let Some(mut buyer_share) = buyer_share {
-            buyer_share.no_of_shares += take;
-            SHARES.save(deps.storage, &buyer_share.id.to_be_bytes(), &buyer_share)?;
-        } else {
-            // else create new share for buyer
-            let new_share_id = SHARE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
-            SHARE_COUNT.save(deps.storage, &new_share_id)?;
-
-            let new_share = Share {
-                id: new_share_id,
-                stock_id,
-                no_of_shares: take,
-                owner: buy_order.owner.clone(),
-            };
-
-            SHARES.save(deps.storage, &new_share_id.to_be_bytes(), &new_share)?;
-        }
+        debit_shares(deps.storage, stock_id, &info.sender, take)?;
+        credit_shares(deps.storage, stock_id, &buy_order.owner, take)?;
 
         // Create Sale record
         let sale_id = SALE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
@@ -391,17 +387,24 @@ pub fn create_sell_order(
             from: info.sender.clone(),
             to: buy_order.owner.clone(),
             created_at: current_timestamp,
+            fee,
+            royalty,
         };
 
         SALES.save(deps.storage, &sale_id.to_be_bytes(), &sale)?;
 
-        let cost = take as u128 * buy_order.price_per_share;
+        // Pay the seller out of the buy order's escrow
+        withdraw_buy_order_escrow(deps.storage, buy_order.id, cost)?;
 
         // Send funds to seller
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: coins(cost, DENOM),
-        }));
+        let proceeds = cost - fee - royalty;
+
+        if proceeds > 0 {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(proceeds, &config.denom),
+            }));
+        }
 
         remaining_shares -= take;
         if remaining_shares == 0 {
@@ -409,6 +412,17 @@ pub fn create_sell_order(
         }
     }
 
+    // Pay the influencer's royalty
+    if total_royalty > 0 {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: royalty_recipient(deps.storage, &stock)?.to_string(),
+            amount: coins(total_royalty, &config.denom),
+        }));
+    }
+
+    // Lock the shares left for sale so they can't be sold twice
+    lock_shares(deps.storage, stock_id, &info.sender, remaining_shares)?;
+
     // Create SellOrder
     let resolved_at = if remaining_shares > 0 {
         None
@@ -432,6 +446,10 @@ pub fn create_sell_order(
 
     SELL_ORDERS.save(deps.storage, &sell_order_id.to_be_bytes(), &sell_order)?;
 
+    if sell_order.resolved_at.is_none() {
+        add_open_sell_order(deps.storage, &sell_order)?;
+    }
+
     let response = Response::new()
         .add_attribute("action", "create_sell_order")
         .add_attribute("stock_id", stock_id.to_string())
@@ -452,6 +470,9 @@ pub fn quick_buy(
     shares: u64,
     slippage: u64,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_not_halted(deps.storage, stock_id)?;
+
     // Validate inputs
     if shares == 0 {
         return Err(ContractError::GenericError(f!("Cannot quick buy 0 shares")));
@@ -464,7 +485,8 @@ pub fn quick_buy(
 
     let current_timestamp = env.block.time.nanos() / 1_000_000;
 
-    if stock.auction_end.is_none() || stock.auction_end > Some(current_timestamp) {
+    // Trading opens once the auction has been settled
+    if !stock.is_settled() {
         return Err(ContractError::GenericError(f!("Stock is not in sale")));
     }
 
@@ -478,10 +500,10 @@ pub fn quick_buy(
     let max_price_with_slippage = total_price + (total_price * slippage as u128 / 100);
 
     // Check if the user sent enough funds
-    let sent_funds = info.funds.iter().find(|coin| coin.denom == DENOM);
+    let sent_funds = info.funds.iter().find(|coin| coin.denom == config.denom);
 
     if sent_funds.is_none() {
-        return Err(ContractError::GenericError(f!("No {DENOM} sent")));
+        return Err(ContractError::GenericError(f!("No {} sent", config.denom)));
     }
 
     let sent_amount = sent_funds.unwrap().amount.u128();
@@ -492,21 +514,13 @@ pub fn quick_buy(
         )));
     }
 
-    // Get sell orders to match with
-    let open_sell_orders = query::orders::get_open_sell_orders_by_stock_id(
-        deps.as_ref(),
-        env.clone(),
-        stock_id,
-        OrderSort::PriceAsc,
-    )?
-    .orders;
-
     let mut remaining_shares = shares;
     let mut messages = vec![];
+    let mut total_royalty = 0u128;
     let mut actual_cost = 0u128;
 
-    // match sell orders
-    for mut sell_order in open_sell_orders {
+    // match sell orders, walking the book from the cheapest order up
+    while let Some(mut sell_order) = best_sell_order(deps.storage, stock_id)? {
         let balance = sell_order.available_shares - sell_order.sold_shares;
 
         let take = std::cmp::min(remaining_shares, balance);
@@ -526,52 +540,22 @@ pub fn quick_buy(
 
         if sell_order.sold_shares == sell_order.available_shares {
             sell_order.resolved_at = Some(current_timestamp);
+            remove_open_sell_order(deps.storage, &sell_order);
         }
 
         SELL_ORDERS.save(deps.storage, &sell_order.id.to_be_bytes(), &sell_order)?;
 
         actual_cost += batch_cost;
 
-        // Transfer share from seller to buyer
-        let mut seller_share = query::shares::get_shares_by_owner(
-            deps.as_ref(),
-            env.clone(),
-            sell_order.owner.clone(),
-        )?
-        .shares
-        .into_iter()
-        .filter(|share| share.stock_id == stock_id)
-        .nth(0)
-        .ok_or(ContractError::GenericError("This shouldn't happen".into()))?;
-
-        seller_share.no_of_shares -= take;
-        SHARES.save(deps.storage, &seller_share.id.to_be_bytes(), &seller_share)?;
-
-        let buyer_share =
-            query::shares::get_shares_by_owner(deps.as_ref(), env.clone(), info.sender.clone())?
-                .shares
-                .into_iter()
-                .filter(|share| share.stock_id == stock_id)
-                .nth(0);
-
-        // if buyer already have shares in stock, update share count
-        if let Some(mut buyer_share) = buyer_share {
-            buyer_share.no_of_shares += take;
-            SHARES.save(deps.storage, &buyer_share.id.to_be_bytes(), &buyer_share)?;
-        } else {
-            // else create new share for buyer
-            let new_share_id = SHARE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
-            SHARE_COUNT.save(deps.storage, &new_share_id)?;
-
-            let new_share = Share {
-                id: new_share_id,
-                stock_id,
-                no_of_shares: take,
-                owner: info.sender.clone(),
-            };
-
-            SHARES.save(deps.storage, &new_share_id.to_be_bytes(), &new_share)?;
-        }
+        // The protocol fee and royalty come out of the seller's proceeds
+        let fee = take_protocol_fee(deps.storage, &config, batch_cost)?;
+        let royalty = take_royalty(deps.storage, &config, &stock, &sell_order.owner, batch_cost)?;
+        total_royalty += royalty;
+
+        // Transfer shares from seller to buyer,
+        // the sold shares were locked by the sell order
+        debit_locked_shares(deps.storage, stock_id, &sell_order.owner, take)?;
+        credit_shares(deps.storage, stock_id, &info.sender, take)?;
 
         // Create Sale record
         let sale_id = SALE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
@@ -585,15 +569,21 @@ pub fn quick_buy(
             from: sell_order.owner.clone(),
             to: info.sender.clone(),
             created_at: current_timestamp,
+            fee,
+            royalty,
         };
 
         SALES.save(deps.storage, &sale_id.to_be_bytes(), &sale)?;
 
         // Send funds to seller
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: sell_order.owner.to_string(),
-            amount: coins(batch_cost, DENOM),
-        }));
+        let proceeds = batch_cost - fee - royalty;
+
+        if proceeds > 0 {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: sell_order.owner.to_string(),
+                amount: coins(proceeds, &config.denom),
+            }));
+        }
 
         remaining_shares -= take;
         if remaining_shares == 0 {
@@ -601,13 +591,21 @@ pub fn quick_buy(
         }
     }
 
+    // Pay the influencer's royalty
+    if total_royalty > 0 {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: royalty_recipient(deps.storage, &stock)?.to_string(),
+            amount: coins(total_royalty, &config.denom),
+        }));
+    }
+
     // Refund excess funds
     let excess_funds = sent_amount - actual_cost;
 
     if excess_funds > 0 {
         messages.push(CosmosMsg::Bank(BankMsg::Send {
             to_address: info.sender.to_string(),
-            amount: coins(excess_funds, DENOM),
+            amount: coins(excess_funds, &config.denom),
         }));
     }
 
@@ -634,6 +632,9 @@ pub fn quick_sell(
     price_per_share: u128,
     slippage: u64,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_not_halted(deps.storage, stock_id)?;
+
     // Validate inputs
     if shares == 0 {
         return Err(ContractError::GenericError(f!(
@@ -648,25 +649,18 @@ pub fn quick_sell(
 
     let current_timestamp = env.block.time.nanos() / 1_000_000;
 
-    if stock.auction_end.is_none() || stock.auction_end > Some(current_timestamp) {
+    // Trading opens once the auction has been settled
+    if !stock.is_settled() {
         return Err(ContractError::GenericError(f!("Stock is not in sale")));
     }
 
     // Check if the seller has enough shares
-    let mut seller_share =
-        query::shares::get_shares_by_owner(deps.as_ref(), env.clone(), info.sender.clone())?
-            .shares
-            .into_iter()
-            .filter(|share| share.stock_id == stock_id)
-            .nth(0)
-            .ok_or(ContractError::GenericError(
-                "You do not have shares in this stock".into(),
-            ))?;
-
-    if seller_share.no_of_shares < shares {
+    let seller_share = load_position(deps.storage, stock_id, &info.sender)?;
+
+    if seller_share.available_shares() < shares {
         return Err(ContractError::GenericError(f!(
-            "Insufficient shares: have {}, need {shares}",
-            seller_share.no_of_shares
+            "Insufficient shares: have {} available, need {shares}",
+            seller_share.available_shares()
         )));
     }
 
@@ -688,21 +682,14 @@ pub fn quick_sell(
         )));
     }
 
-    // Get buy orders to match with
-    let open_buy_orders = query::orders::get_open_buy_orders_by_stock_id(
-        deps.as_ref(),
-        env.clone(),
-        stock_id,
-        OrderSort::PriceDesc,
-    )?
-    .orders;
-
     let mut remaining_shares = shares;
     let mut messages = vec![];
+    let mut total_royalty = 0u128;
     let mut actual_revenue = 0u128;
+    let mut total_fees = 0u128;
 
-    // Match buy orders
-    for mut buy_order in open_buy_orders {
+    // Match buy orders, walking the book from the highest price down
+    while let Some(mut buy_order) = best_buy_order(deps.storage, stock_id)? {
         let balance = buy_order.requested_shares - buy_order.bought_shares;
 
         let take = std::cmp::min(remaining_shares, balance);
@@ -710,49 +697,30 @@ pub fn quick_sell(
         // Calculate revenue for this batch of shares
         let batch_revenue = take as u128 * buy_order.price_per_share;
 
+        // Pay the seller out of the buy order's escrow
+        withdraw_buy_order_escrow(deps.storage, buy_order.id, batch_revenue)?;
+
         // Update buy order
         buy_order.bought_shares += take;
 
         if buy_order.bought_shares == buy_order.requested_shares {
             buy_order.resolved_at = Some(current_timestamp);
+            remove_open_buy_order(deps.storage, &buy_order);
         }
 
         BUY_ORDERS.save(deps.storage, &buy_order.id.to_be_bytes(), &buy_order)?;
 
         actual_revenue += batch_revenue;
 
+        // The protocol fee and royalty come out of the seller's proceeds
+        let fee = take_protocol_fee(deps.storage, &config, batch_revenue)?;
+        total_fees += fee;
+        let royalty = take_royalty(deps.storage, &config, &stock, &info.sender, batch_revenue)?;
+        total_royalty += royalty;
+
         // Transfer shares from seller to buyer
-        seller_share.no_of_shares -= take;
-        SHARES.save(deps.storage, &seller_share.id.to_be_bytes(), &seller_share)?;
-
-        let buyer_share = query::shares::get_shares_by_owner(
-            deps.as_ref(),
-            env.clone(),
-            buy_order.owner.clone(),
-        )?
-        .shares
-        .into_iter()
-        .filter(|share| share.stock_id == stock_id)
-        .nth(0);
-
-        // If buyer already has shares in stock, update share count
-        if let Some(mut buyer_share) = buyer_share {
-            buyer_share.no_of_shares += take;
-            SHARES.save(deps.storage, &buyer_share.id.to_be_bytes(), &buyer_share)?;
-        } else {
-            // Else create new share for buyer
-            let new_share_id = SHARE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
-            SHARE_COUNT.save(deps.storage, &new_share_id)?;
-
-            let new_share = Share {
-                id: new_share_id,
-                stock_id,
-                no_of_shares: take,
-                owner: buy_order.owner.clone(),
-            };
-
-            SHARES.save(deps.storage, &new_share_id.to_be_bytes(), &new_share)?;
-        }
+        debit_shares(deps.storage, stock_id, &info.sender, take)?;
+        credit_shares(deps.storage, stock_id, &buy_order.owner, take)?;
 
         // Create Sale record
         let sale_id = SALE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
@@ -766,15 +734,21 @@ pub fn quick_sell(
             from: info.sender.clone(),
             to: buy_order.owner.clone(),
             created_at: current_timestamp,
+            fee,
+            royalty,
         };
 
         SALES.save(deps.storage, &sale_id.to_be_bytes(), &sale)?;
 
         // Send funds to seller
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: coins(batch_revenue, DENOM),
-        }));
+        let proceeds = batch_revenue - fee - royalty;
+
+        if proceeds > 0 {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(proceeds, &config.denom),
+            }));
+        }
 
         remaining_shares -= take;
         if remaining_shares == 0 {
@@ -782,6 +756,14 @@ pub fn quick_sell(
         }
     }
 
+    // Pay the influencer's royalty
+    if total_royalty > 0 {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: royalty_recipient(deps.storage, &stock)?.to_string(),
+            amount: coins(total_royalty, &config.denom),
+        }));
+    }
+
     // Verify that all shares were sold
     if remaining_shares > 0 {
         return Err(ContractError::GenericError(f!(
@@ -794,6 +776,7 @@ pub fn quick_sell(
         .add_attribute("stock_id", stock_id.to_string())
         .add_attribute("shares", shares.to_string())
         .add_attribute("revenue", actual_revenue.to_string())
+        .add_attribute("fee", total_fees.to_string())
         .add_attribute(
             "average_price_per_share",
             (actual_revenue / shares as u128).to_string(),
@@ -0,0 +1,117 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    contract::access::ensure_owner,
+    state::{Role, ROLES, STATE},
+    ContractError,
+};
+
+use format as f;
+
+// First step of an ownership transfer, the new owner has to accept it
+pub fn propose_new_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    state.pending_owner = Some(new_owner.clone());
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_new_owner")
+        .add_attribute("pending_owner", new_owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+
+    if state.pending_owner.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let previous_owner = state.owner.take();
+
+    state.owner = Some(info.sender.clone());
+    state.pending_owner = None;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute(
+            "previous_owner",
+            previous_owner.map_or("none".to_string(), |owner| owner.to_string()),
+        )
+        .add_attribute("owner", info.sender))
+}
+
+// Leaves the contract without an owner, this cannot be undone
+pub fn renounce_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    state.owner = None;
+    state.pending_owner = None;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "renounce_ownership")
+        .add_attribute("previous_owner", info.sender))
+}
+
+pub fn grant_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.save(deps.storage, (role.as_str(), &address), &())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address))
+}
+
+pub fn revoke_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+
+    if !ROLES.has(deps.storage, (role.as_str(), &address)) {
+        return Err(ContractError::NotFound(f!(
+            "{} role for {address}",
+            role.as_str()
+        )));
+    }
+
+    ROLES.remove(deps.storage, (role.as_str(), &address));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address))
+}
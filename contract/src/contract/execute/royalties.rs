@@ -0,0 +1,55 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    state::{CONFIG, ROYALTIES, STOCKS},
+    ContractError,
+};
+
+use format as f;
+
+pub fn set_royalty(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+    royalty_bps: u16,
+    payout_address: Option<String>,
+) -> Result<Response, ContractError> {
+    let stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
+
+    if stock.influencer != info.sender {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    if royalty_bps > config.max_royalty_bps {
+        return Err(ContractError::GenericError(f!(
+            "Royalty cannot exceed {} basis points",
+            config.max_royalty_bps
+        )));
+    }
+
+    let payout_address = payout_address
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    let mut royalty = ROYALTIES
+        .may_load(deps.storage, &stock_id.to_be_bytes())?
+        .unwrap_or_default();
+
+    royalty.royalty_bps = royalty_bps;
+    royalty.payout_address = payout_address;
+
+    ROYALTIES.save(deps.storage, &stock_id.to_be_bytes(), &royalty)?;
+
+    let payout_address = royalty.payout_address.unwrap_or(stock.influencer);
+
+    Ok(Response::new()
+        .add_attribute("action", "set_royalty")
+        .add_attribute("stock_id", stock_id.to_string())
+        .add_attribute("royalty_bps", royalty_bps.to_string())
+        .add_attribute("payout_address", payout_address))
+}
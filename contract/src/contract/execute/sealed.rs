@@ -0,0 +1,273 @@
+use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    contract::{escrow::deposit_auction_escrow, halt::ensure_not_halted, query::bids::opening_bid},
+    state::{Bid, SealedBid, Stock, BIDS, BID_COUNT, CONFIG, REVEALED_BIDS, SEALED_BIDS, STOCKS},
+    ContractError,
+};
+
+use format as f;
+
+// Hex encoded sha256 of "{bidder}:{price_per_share}:{shares}:{salt}",
+// what a bidder commits to before revealing their bid
+pub fn sealed_bid_commitment(
+    bidder: &Addr,
+    price_per_share: u128,
+    shares: u64,
+    salt: &str,
+) -> String {
+    let preimage = f!("{bidder}:{price_per_share}:{shares}:{salt}");
+
+    hex::encode(Sha256::digest(preimage.as_bytes()))
+}
+
+pub fn commit_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+    commitment: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_not_halted(deps.storage, stock_id)?;
+
+    let deposit = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == config.denom)
+        .map_or(0, |coin| coin.amount.u128());
+
+    if deposit == 0 {
+        return Err(ContractError::GenericError(f!("No {} sent", config.denom)));
+    }
+
+    let commitment = commitment.to_lowercase();
+
+    if commitment.len() != 64 || !commitment.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ContractError::GenericError(
+            "Commitment must be a hex encoded sha256 hash".to_string(),
+        ));
+    }
+
+    let stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
+
+    if stock.sealed_bid.is_none() {
+        return Err(ContractError::GenericError(f!(
+            "Stock {stock_id} doesn't take sealed bids"
+        )));
+    }
+
+    let Some(auction_start) = stock.auction_start else {
+        return Err(ContractError::GenericError(
+            "Stock is yet to be auctioned".to_string(),
+        ));
+    };
+
+    let current_time = env.block.time.nanos() / 1_000_000;
+
+    if current_time < auction_start {
+        return Err(ContractError::GenericError(f!(
+            "Auction starts at {auction_start}"
+        )));
+    }
+
+    if stock.settling || stock.auction_end.is_some_and(|end| current_time > end) {
+        return Err(ContractError::GenericError(
+            "Commit phase has ended".to_string(),
+        ));
+    }
+
+    // The bid stays empty until it is revealed
+    let bid_id = BID_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
+    BID_COUNT.save(deps.storage, &bid_id)?;
+
+    let bid = Bid {
+        id: bid_id,
+        stock_id,
+        bidder: info.sender.clone(),
+        price_per_share: 0,
+        shares_requested: 0,
+        remaining_shares: 0,
+        created_at: current_time,
+        open: false,
+        active: true,
+    };
+
+    BIDS.save(deps.storage, &bid_id.to_be_bytes(), &bid)?;
+
+    let sealed_bid = SealedBid {
+        bid_id,
+        commitment,
+        deposit,
+        revealed: false,
+    };
+
+    SEALED_BIDS.save(deps.storage, &bid_id.to_be_bytes(), &sealed_bid)?;
+
+    // Held until the auction is settled
+    deposit_auction_escrow(deps.storage, stock_id, deposit)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit_bid")
+        .add_attribute("bid_id", bid_id.to_string())
+        .add_attribute("stock_id", stock_id.to_string())
+        .add_attribute("bidder", info.sender.to_string())
+        .add_attribute("deposit", deposit.to_string()))
+}
+
+pub fn reveal_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bid_id: u64,
+    price_per_share: u128,
+    shares: u64,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut bid = BIDS
+        .load(deps.storage, &bid_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Bid with id {bid_id}")))?;
+
+    let mut sealed_bid = SEALED_BIDS
+        .may_load(deps.storage, &bid_id.to_be_bytes())?
+        .ok_or_else(|| ContractError::GenericError(f!("Bid {bid_id} is not a sealed bid")))?;
+
+    if bid.bidder != info.sender {
+        return Err(ContractError::Unauthorized);
+    }
+
+    // Reveals stay open during a halt, the reveal phase ends when it was set to
+
+    let stock = STOCKS
+        .load(deps.storage, &bid.stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {}", bid.stock_id)))?;
+
+    let current_time = env.block.time.nanos() / 1_000_000;
+    let auction_end = stock.auction_end.unwrap_or(current_time);
+
+    if current_time <= auction_end {
+        return Err(ContractError::GenericError(f!(
+            "Reveal phase starts after {auction_end}"
+        )));
+    }
+
+    if stock.settling || stock.reveal_end().is_some_and(|end| current_time > end) {
+        return Err(ContractError::GenericError(
+            "Reveal phase has ended".to_string(),
+        ));
+    }
+
+    if sealed_bid.revealed {
+        return Err(ContractError::GenericError(f!(
+            "Bid {bid_id} has already been revealed"
+        )));
+    }
+
+    if sealed_bid_commitment(&info.sender, price_per_share, shares, &salt) != sealed_bid.commitment
+    {
+        return Err(ContractError::GenericError(
+            "Revealed bid doesn't match its commitment".to_string(),
+        ));
+    }
+
+    if shares == 0 || shares > stock.auctioned_shares() {
+        return Err(ContractError::GenericError(f!(
+            "Shares must be between 1 and {}",
+            stock.auctioned_shares()
+        )));
+    }
+
+    if price_per_share < stock.reserve_price {
+        return Err(ContractError::GenericError(f!(
+            "Bid price is below the reserve price of {}",
+            stock.reserve_price
+        )));
+    }
+
+    let cost = price_per_share * shares as u128;
+
+    if cost > sealed_bid.deposit {
+        return Err(ContractError::GenericError(f!(
+            "Deposit of {} doesn't cover the bid's {cost}",
+            sealed_bid.deposit
+        )));
+    }
+
+    // The bid takes part in the auction once settlement allocates it
+    bid.price_per_share = price_per_share;
+    bid.shares_requested = shares;
+    bid.remaining_shares = shares;
+    BIDS.save(deps.storage, &bid_id.to_be_bytes(), &bid)?;
+
+    // Ranked among the stock's other revealed bids
+    REVEALED_BIDS.save(deps.storage, revealed_bid_key(&bid), &())?;
+
+    sealed_bid.revealed = true;
+    SEALED_BIDS.save(deps.storage, &bid_id.to_be_bytes(), &sealed_bid)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reveal_bid")
+        .add_attribute("bid_id", bid_id.to_string())
+        .add_attribute("stock_id", bid.stock_id.to_string())
+        .add_attribute("bidder", info.sender.to_string())
+        .add_attribute("price_per_share", price_per_share.to_string())
+        .add_attribute("shares", shares.to_string()))
+}
+
+// Revealed bids are indexed by inverted price so the highest price sorts first
+pub fn revealed_bid_key(bid: &Bid) -> (u64, u128, u64) {
+    (bid.stock_id, u128::MAX - bid.price_per_share, bid.id)
+}
+
+// Allocate the auctioned shares to up to `limit` revealed bids of a sealed bid auction,
+// highest price first and at the same price in the order they were committed.
+// Whatever isn't allocated stays with the influencer's opening bid.
+// What they pay is added to the stock's amount_raised, the caller saves the stock.
+// Returns the number of bids that were allocated and whether allocation is done.
+pub fn allocate_sealed_bids(
+    storage: &mut dyn Storage,
+    stock: &mut Stock,
+    limit: u32,
+) -> Result<(u64, bool), ContractError> {
+    let mut opening_bid = opening_bid(storage, stock)?
+        .ok_or_else(|| ContractError::NotFound(f!("Opening bid of stock {}", stock.id)))?;
+
+    // One more than the limit tells whether this is the last page
+    let ranked_bids = REVEALED_BIDS
+        .sub_prefix(stock.id)
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit as usize + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut done = ranked_bids.len() <= limit as usize;
+    let mut allocated = 0;
+
+    for (_, bid_id) in ranked_bids.into_iter().take(limit as usize) {
+        // The bids that are left get nothing, settlement refunds them
+        if opening_bid.remaining_shares == 0 {
+            done = true;
+            break;
+        }
+
+        let mut bid = BIDS.load(storage, &bid_id.to_be_bytes())?;
+
+        let allocated_shares = bid.remaining_shares.min(opening_bid.remaining_shares);
+        opening_bid.remaining_shares -= allocated_shares;
+
+        bid.remaining_shares = allocated_shares;
+        bid.open = true;
+        stock.amount_raised += bid.price_per_share * allocated_shares as u128;
+        BIDS.save(storage, &bid.id.to_be_bytes(), &bid)?;
+
+        REVEALED_BIDS.remove(storage, revealed_bid_key(&bid));
+        allocated += 1;
+    }
+
+    opening_bid.open = opening_bid.remaining_shares > 0;
+    BIDS.save(storage, &opening_bid.id.to_be_bytes(), &opening_bid)?;
+
+    Ok((allocated, done || opening_bid.remaining_shares == 0))
+}
@@ -0,0 +1,460 @@
+use cosmwasm_std::{
+    coins, Addr, BankMsg, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage,
+};
+use cw_storage_plus::Bound;
+
+use crate::{
+    contract::{
+        access::has_role,
+        escrow::{release_auction_escrow, withdraw_auction_escrow},
+        execute::{
+            sealed::{allocate_sealed_bids, revealed_bid_key},
+            vesting::start_vesting,
+        },
+        fees::{calculate_fee, take_protocol_fee},
+        positions::credit_shares,
+        query::bids::opening_bid,
+    },
+    state::{
+        Config, Role, Sale, Settlement, Stock, AUCTION_ESCROWS, BIDS, CONFIG, REVEALED_BIDS, SALES,
+        SALE_COUNT, SEALED_BIDS, SETTLEMENTS, STOCKS,
+    },
+    ContractError,
+};
+
+use format as f;
+
+// Bids settled per call when no limit is given
+pub const DEFAULT_SETTLEMENT_LIMIT: u32 = 100;
+pub const MAX_SETTLEMENT_LIMIT: u32 = 500;
+
+// What a single settlement batch did
+pub struct SettlementBatch {
+    pub settled_bids: u64,
+    pub allocated_bids: u64,
+    pub proceeds: u128,
+    pub fee: u128,
+    pub keeper_reward: u128,
+    // Paid back to winners of a uniform price auction who bid above the clearing price
+    // and to sealed bidders out of their deposits
+    pub refunds: u128,
+    // The auction missed its funding goal, its bids are refunded instead of settled
+    pub goal_missed: bool,
+    pub complete: bool,
+    pub messages: Vec<BankMsg>,
+}
+
+// Close the auction so it can be settled.
+// Anyone can once it is over, operators (and the owner) and the influencer can also end it early.
+pub fn start_settlement(
+    storage: &mut dyn Storage,
+    current_time: u64,
+    sender: &Addr,
+    stock: &mut Stock,
+) -> Result<(), ContractError> {
+    // Check if auction is ended and stock is in sale
+    // and marked as inactive
+    if let Some(end_timestamp) = stock.auction_end {
+        if current_time > end_timestamp && !stock.marked_as_active_auction {
+            return Err(ContractError::GenericError(
+                "Stock has already been auctioned and in sale".to_string(),
+            ));
+        }
+    }
+
+    // Check stock hasn't started auction
+    if stock.auction_start.is_none() {
+        return Err(ContractError::GenericError(
+            "Stock is yet to be auctioned".to_string(),
+        ));
+    }
+
+    if stock.is_settled() {
+        return Err(ContractError::GenericError(
+            "Auction has already been settled".to_string(),
+        ));
+    }
+
+    if stock.settling {
+        return Err(ContractError::GenericError(f!(
+            "Auction of stock {} is already being settled",
+            stock.id
+        )));
+    }
+
+    let is_operator = has_role(storage, Role::Operator, sender)?;
+    let is_influencer = stock.influencer == *sender;
+    // A sealed bid auction is over once its reveal phase is,
+    // a Dutch auction also once it has sold out
+    let is_over = stock
+        .reveal_end()
+        .or(stock.auction_end)
+        .is_some_and(|end_timestamp| current_time > end_timestamp)
+        || (stock.dutch_auction.is_some()
+            && opening_bid(storage, stock)?.is_none_or(|bid| bid.remaining_shares == 0));
+
+    // Ending early would leave bidders no way to reveal
+    if let Some(reveal_end) = stock.reveal_end() {
+        if !is_over {
+            return Err(ContractError::GenericError(f!(
+                "Reveal phase ends at {reveal_end}"
+            )));
+        }
+    }
+
+    if !is_over && !is_operator && !is_influencer {
+        return Err(ContractError::Unauthorized);
+    }
+
+    // Update auction end time to current block time
+    // if less than current time
+    if let Some(end_timestamp) = stock.auction_end {
+        if current_time < end_timestamp {
+            stock.auction_end = Some(current_time);
+        }
+    }
+
+    // Sealed bids are allocated before the clearing price can be worked out
+    let allocating = stock.sealed_bid.is_some();
+
+    if stock.uniform_price && !allocating {
+        stock.clearing_price = Some(clearing_price(storage, stock)?);
+    }
+
+    let settlement = Settlement {
+        allocating,
+        // Sealed bids only count towards the goal once they are allocated
+        goal_missed: !allocating && goal_missed(storage, stock)?,
+        ..Settlement::default()
+    };
+
+    // No more bids from here on
+    stock.settling = true;
+
+    STOCKS.save(storage, &stock.id.to_be_bytes(), stock)?;
+    SETTLEMENTS.save(storage, &stock.id.to_be_bytes(), &settlement)?;
+
+    Ok(())
+}
+
+// The highest price at which the whole supply is covered, which is the lowest open bid.
+// The influencer's opening bid sets no price, so an undersubscribed auction
+// clears at its lowest bid, or at the reserve price when there are none.
+fn clearing_price(storage: &dyn Storage, stock: &Stock) -> StdResult<u128> {
+    // Open bids are indexed by price, the first one past the opening bid is the lowest
+    let lowest_bid = BIDS
+        .idx
+        .stock_open
+        .sub_prefix((stock.id, 1))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, bid)| bid))
+        .find(|bid| bid.as_ref().map_or(true, |bid| bid.price_per_share > 0))
+        .transpose()?;
+
+    let lowest_price = lowest_bid.map_or(0, |bid| bid.price_per_share);
+
+    Ok(lowest_price.max(stock.reserve_price))
+}
+
+// Whether the auction raised less than its funding goal. Winners of a uniform price auction
+// all pay the clearing price, for every share that has left the opening bid.
+fn goal_missed(storage: &dyn Storage, stock: &Stock) -> StdResult<bool> {
+    let Some(funding_goal) = stock.funding_goal else {
+        return Ok(false);
+    };
+
+    let amount_raised = match stock.clearing_price {
+        Some(clearing_price) => {
+            let unsold = opening_bid(storage, stock)?.map_or(0, |bid| bid.remaining_shares);
+            clearing_price * (stock.auctioned_shares() - unsold) as u128
+        }
+        None => stock.amount_raised,
+    };
+
+    Ok(amount_raised < funding_goal)
+}
+
+// Settle up to `limit` of the auction's bids, resuming after the last one settled.
+// Winning bids become positions and sales. The revealed bids of a sealed bid auction
+// are allocated first, which takes from the same limit.
+// The auction is settled once every bid has been through. When it missed its funding goal
+// every bid is refunded instead and the stock goes back to never having been auctioned.
+pub fn settle_batch(
+    storage: &mut dyn Storage,
+    config: &Config,
+    current_time: u64,
+    keeper: &Addr,
+    stock: &mut Stock,
+    limit: u32,
+) -> Result<SettlementBatch, ContractError> {
+    let stock_id = stock.id;
+    let mut settlement = SETTLEMENTS.load(storage, &stock_id.to_be_bytes())?;
+    let mut limit = limit;
+
+    if settlement.allocating {
+        let (allocated, done) = allocate_sealed_bids(storage, stock, limit)?;
+        settlement.allocated_bids += allocated;
+        limit -= allocated as u32;
+
+        if done {
+            settlement.allocating = false;
+
+            if stock.uniform_price {
+                stock.clearing_price = Some(clearing_price(storage, stock)?);
+            }
+
+            settlement.goal_missed = goal_missed(storage, stock)?;
+        }
+
+        STOCKS.save(storage, &stock_id.to_be_bytes(), stock)?;
+
+        // Bids are only settled with what is left of the limit
+        if settlement.allocating || limit == 0 {
+            SETTLEMENTS.save(storage, &stock_id.to_be_bytes(), &settlement)?;
+
+            return Ok(SettlementBatch {
+                settled_bids: settlement.settled_bids,
+                allocated_bids: settlement.allocated_bids,
+                proceeds: 0,
+                fee: 0,
+                keeper_reward: 0,
+                refunds: 0,
+                goal_missed: false,
+                complete: false,
+                messages: vec![],
+            });
+        }
+    }
+
+    // Bids of earlier auctions that missed their goal come before the opening bid
+    let start = settlement
+        .last_bid_id
+        .map(|bid_id| Bound::ExclusiveRaw(bid_id.to_be_bytes().to_vec()))
+        .or_else(|| {
+            stock
+                .opening_bid_id
+                .map(|bid_id| Bound::InclusiveRaw(bid_id.to_be_bytes().to_vec()))
+        });
+
+    // One more than the limit tells whether this is the last batch
+    let mut bids = BIDS
+        .idx
+        .stock_id
+        .prefix(stock_id)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit as usize + 1)
+        .map(|item| item.map(|(_, bid)| bid))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let complete = bids.len() <= limit as usize;
+    bids.truncate(limit as usize);
+
+    let mut proceeds = 0u128;
+    let mut fee = 0u128;
+    let mut refunds = 0u128;
+    let mut messages = vec![];
+
+    for mut bid in bids {
+        // What goes back to the bidder
+        let mut refund = 0;
+
+        // Sealed bids get back what their deposit doesn't pay for.
+        // Part of an unrevealed bid's deposit is kept and goes to the influencer.
+        // Every bid gets back all it holds when the funding goal was missed.
+        if let Some(sealed_bid) = SEALED_BIDS.may_load(storage, &bid.id.to_be_bytes())? {
+            refund += if !sealed_bid.revealed && !settlement.goal_missed {
+                sealed_bid.deposit - calculate_fee(sealed_bid.deposit, config.unrevealed_slash_bps)
+            } else if bid.open && !settlement.goal_missed {
+                sealed_bid.deposit - bid.price_per_share * bid.remaining_shares as u128
+            } else {
+                // Allocation stopped before it reached the bid or the goal was missed,
+                // it gets no shares
+                REVEALED_BIDS.remove(storage, revealed_bid_key(&bid));
+                bid.remaining_shares = 0;
+
+                sealed_bid.deposit
+            };
+        } else if settlement.goal_missed {
+            refund += bid.price_per_share * bid.remaining_shares as u128;
+        }
+
+        // Open bids are the winning ones
+        if bid.open && !settlement.goal_missed {
+            // Winners of a uniform price auction pay the clearing price
+            // (the influencer's opening bid stays at 0)
+            let price_per_share = stock
+                .clearing_price
+                .map_or(bid.price_per_share, |price| price.min(bid.price_per_share));
+
+            let cost = price_per_share * bid.remaining_shares as u128;
+            refund += (bid.price_per_share - price_per_share) * bid.remaining_shares as u128;
+
+            // Credit the winning shares to the bidder's position
+            credit_shares(storage, stock_id, &bid.bidder, bid.remaining_shares)?;
+
+            // The whole bid was held in escrow, the fee comes out of it now
+            let bid_fee = take_protocol_fee(storage, config, cost)?;
+            withdraw_auction_escrow(storage, stock_id, bid_fee)?;
+
+            proceeds += cost;
+            fee += bid_fee;
+
+            // Create Sale record
+            let sale_id = SALE_COUNT.may_load(storage)?.unwrap_or(0) + 1;
+            SALE_COUNT.save(storage, &sale_id)?;
+
+            let sale = Sale {
+                id: sale_id,
+                stock_id,
+                no_of_shares: bid.remaining_shares,
+                price_per_share,
+                from: stock.influencer.clone(),
+                to: bid.bidder.clone(),
+                created_at: current_time,
+                fee: bid_fee,
+                // Auction sales are primary sales, no royalty
+                royalty: 0,
+            };
+
+            SALES.save(storage, &sale_id.to_be_bytes(), &sale)?;
+        }
+
+        if refund > 0 {
+            withdraw_auction_escrow(storage, stock_id, refund)?;
+            refunds += refund;
+
+            messages.push(BankMsg::Send {
+                to_address: bid.bidder.to_string(),
+                amount: coins(refund, &config.denom),
+            });
+        }
+
+        // close bid
+        bid.open = false;
+        bid.active = false;
+        BIDS.save(storage, &bid.id.to_be_bytes(), &bid)?;
+
+        settlement.last_bid_id = Some(bid.id);
+        settlement.settled_bids += 1;
+    }
+
+    // Whoever settles the batch for the influencer is rewarded out of its proceeds,
+    // from what the auction's escrow holds for the influencer
+    let keeper_reward = if *keeper == stock.influencer {
+        0
+    } else {
+        let held = AUCTION_ESCROWS
+            .may_load(storage, &stock_id.to_be_bytes())?
+            .unwrap_or(0);
+
+        // A Dutch auction sold its shares before settlement,
+        // everything held back from those sales is the reward
+        if stock.dutch_auction.is_some() {
+            held
+        } else {
+            calculate_fee(proceeds, config.keeper_reward_bps).min(held)
+        }
+    };
+
+    withdraw_auction_escrow(storage, stock_id, keeper_reward)?;
+
+    let mut influencer_pay = 0;
+
+    if complete {
+        // Anything left in escrow belongs to the influencer
+        influencer_pay = release_auction_escrow(storage, stock_id)?;
+
+        if settlement.goal_missed {
+            // The influencer can start over with a new auction
+            stock.reset_auction();
+        } else {
+            stock.settling = false;
+            stock.marked_as_active_auction = false;
+
+            start_vesting(storage, stock, current_time)?;
+        }
+
+        STOCKS.save(storage, &stock_id.to_be_bytes(), stock)?;
+        SETTLEMENTS.remove(storage, &stock_id.to_be_bytes());
+    } else {
+        SETTLEMENTS.save(storage, &stock_id.to_be_bytes(), &settlement)?;
+    }
+
+    if keeper_reward > 0 {
+        messages.push(BankMsg::Send {
+            to_address: keeper.to_string(),
+            amount: coins(keeper_reward, &config.denom),
+        });
+    }
+
+    if influencer_pay > 0 {
+        messages.push(BankMsg::Send {
+            to_address: stock.influencer.to_string(),
+            amount: coins(influencer_pay, &config.denom),
+        });
+    }
+
+    Ok(SettlementBatch {
+        settled_bids: settlement.settled_bids,
+        allocated_bids: settlement.allocated_bids,
+        proceeds,
+        fee,
+        keeper_reward,
+        refunds,
+        goal_missed: settlement.goal_missed,
+        complete,
+        messages,
+    })
+}
+
+// Settle the next batch of an auction's bids, closing the auction first if needed
+pub fn settle_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let limit = limit.unwrap_or(DEFAULT_SETTLEMENT_LIMIT);
+
+    if limit == 0 || limit > MAX_SETTLEMENT_LIMIT {
+        return Err(ContractError::GenericError(f!(
+            "Limit must be between 1 and {MAX_SETTLEMENT_LIMIT}"
+        )));
+    }
+
+    let mut stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
+
+    let current_time = env.block.time.nanos() / 1_000_000;
+
+    if !stock.settling {
+        start_settlement(deps.storage, current_time, &info.sender, &mut stock)?;
+    }
+
+    let batch = settle_batch(
+        deps.storage,
+        &config,
+        current_time,
+        &info.sender,
+        &mut stock,
+        limit,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "settle_auction")
+        .add_attribute("stock_id", stock_id.to_string())
+        .add_attribute("settled_by", info.sender)
+        .add_attribute("settled_bids", batch.settled_bids.to_string())
+        .add_attribute("allocated_bids", batch.allocated_bids.to_string())
+        .add_attribute("complete", batch.complete.to_string())
+        .add_attribute("proceeds", batch.proceeds.to_string())
+        .add_attribute("fee", batch.fee.to_string())
+        .add_attribute("keeper_reward", batch.keeper_reward.to_string())
+        .add_attribute("refunds", batch.refunds.to_string())
+        .add_attribute("goal_missed", batch.goal_missed.to_string())
+        .add_messages(batch.messages))
+}
@@ -1,45 +1,109 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response};
 
 use crate::{
-    contract::query,
+    contract::{
+        access::ensure_role,
+        execute::dutch::validate_dutch_auction,
+        execute::settlement::{settle_batch, start_settlement, DEFAULT_SETTLEMENT_LIMIT},
+        fees::MAX_FEE_BPS,
+        merkle::parse_hash,
+        metadata::{metadata_event, validate_metadata},
+        tickers::{normalize_ticker, validate_ticker},
+    },
     state::{
-        Bid, Sale, Share, Stock, BIDS, BID_COUNT, SALES, SALE_COUNT, SHARES, SHARE_COUNT, STATE,
-        STOCKS, STOCK_COUNT,
+        Bid, DutchAuction, Presale, RetainedAllocation, Role, SealedBidAuction, Stock,
+        StockMetadata, BIDS, BID_COUNT, CONFIG, RESERVED_TICKERS, STOCKS, STOCK_COUNT, TICKERS,
     },
     ContractError,
 };
 
 use format as f;
 
-// total shares for a stock fixed at 1_000_000 for now,
-// may be updated to vary based on creators choice
+// Default number of shares a stock is created with
 pub const TOTAL_SHARES: u64 = 1_000_000;
 
+// Default auction length (24 hours in milliseconds)
+pub const AUCTION_DURATION: u64 = 24 * 60 * 60 * 1000;
+
 pub fn create_stock(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     ticker: String,
+    total_shares: Option<u64>,
+    metadata: Option<StockMetadata>,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let total_shares = total_shares.unwrap_or(config.total_shares);
+
+    if total_shares < config.min_total_shares || total_shares > config.max_total_shares {
+        return Err(ContractError::GenericError(f!(
+            "Total shares must be between {} and {}",
+            config.min_total_shares,
+            config.max_total_shares
+        )));
+    }
+
+    let ticker = normalize_ticker(&ticker);
+    validate_ticker(&ticker)?;
+
+    if let Some(holder_stock_id) = TICKERS.may_load(deps.storage, &ticker)? {
+        return Err(ContractError::GenericError(f!(
+            "Ticker {ticker} is already used by stock {holder_stock_id}"
+        )));
+    }
+
+    // A reserved ticker can only be claimed by its holder, which uses up the reservation
+    if let Some(reservation) = RESERVED_TICKERS.may_load(deps.storage, &ticker)? {
+        if reservation.holder.as_ref() != Some(&info.sender) {
+            return Err(ContractError::GenericError(f!(
+                "Ticker {ticker} is reserved"
+            )));
+        }
+
+        RESERVED_TICKERS.remove(deps.storage, &ticker);
+    }
+
+    let metadata = metadata.unwrap_or_default();
+    validate_metadata(&metadata)?;
+
     // Validate addresses
     let influencer_addr = info.sender.clone();
 
     // increment and save stock_id counter
     let stock_id = STOCK_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
     STOCK_COUNT.save(deps.storage, &stock_id)?;
+    TICKERS.save(deps.storage, &ticker, &stock_id)?;
 
     let created_at = env.block.time.nanos() / 1_000_000;
 
     // Create new stock
     let stock = Stock {
         id: stock_id,
-        ticker,
+        ticker: ticker.clone(),
         influencer: influencer_addr,
-        total_shares: TOTAL_SHARES,
+        total_shares,
         auction_start: None,
         auction_end: None,
+        max_auction_end: None,
+        reserve_price: 0,
+        uniform_price: false,
+        clearing_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        amount_raised: 0,
+        opening_bid_id: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
         marked_as_active_auction: false,
+        settling: false,
         created_at,
+        delisted_at: None,
+        metadata,
     };
 
     // Save the stock
@@ -48,9 +112,26 @@ pub fn create_stock(
     Ok(Response::new()
         .add_attribute("action", "create_stock")
         .add_attribute("stock_id", stock_id.to_string())
+        .add_attribute("ticker", ticker)
         .add_attribute("influencer", info.sender)
         .add_attribute("created_at", created_at.to_string())
-        .add_attribute("total_shares", TOTAL_SHARES.to_string()))
+        .add_attribute("total_shares", total_shares.to_string())
+        .add_event(metadata_event(stock_id, &stock.metadata)))
+}
+
+// How an auction is run, see ExecuteMsg::StartAuction
+pub struct AuctionParams {
+    pub start_at: Option<u64>,
+    pub duration: Option<u64>,
+    pub reserve_price: Option<u128>,
+    pub uniform_price: Option<bool>,
+    pub dutch_auction: Option<DutchAuction>,
+    pub sealed_bid: Option<SealedBidAuction>,
+    pub funding_goal: Option<u128>,
+    pub max_shares_per_address: Option<u64>,
+    pub min_shares_per_bid: Option<u64>,
+    pub presale: Option<Presale>,
+    pub retained_allocation: Option<RetainedAllocation>,
 }
 
 pub fn start_auction(
@@ -58,7 +139,10 @@ pub fn start_auction(
     env: Env,
     info: MessageInfo,
     stock_id: u64,
+    params: AuctionParams,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
     // Convert stock_id from u64 into be_bytes
     let stock_id_bytes = stock_id.to_be_bytes();
 
@@ -73,6 +157,12 @@ pub fn start_auction(
         return Err(ContractError::Unauthorized);
     }
 
+    if stock.delisted_at.is_some() {
+        return Err(ContractError::GenericError(f!(
+            "Stock {stock_id} is delisted"
+        )));
+    }
+
     // Get current blockchain time in milliseconds
     let current_time = env.block.time.nanos() / 1_000_000; // Convert nanos to millis
 
@@ -92,33 +182,126 @@ pub fn start_auction(
         )));
     }
 
-    // Set auction start time to current blockchain time
-    let start_timestamp = current_time;
-    stock.auction_start = Some(current_time);
+    // Start now unless the auction is scheduled for later
+    let start_timestamp = params.start_at.unwrap_or(current_time);
+
+    if start_timestamp < current_time {
+        return Err(ContractError::GenericError(
+            "Auction cannot start in the past".to_string(),
+        ));
+    }
+
+    let duration = params.duration.unwrap_or(config.auction_duration);
+
+    if duration < config.min_auction_duration || duration > config.max_auction_duration {
+        return Err(ContractError::GenericError(f!(
+            "Auction duration must be between {} and {} milliseconds",
+            config.min_auction_duration,
+            config.max_auction_duration
+        )));
+    }
+
+    if let Some(dutch_auction) = &params.dutch_auction {
+        validate_dutch_auction(dutch_auction)?;
+
+        // The price schedule takes the place of bidding
+        if params.reserve_price.is_some() || params.uniform_price == Some(true) {
+            return Err(ContractError::GenericError(
+                "A Dutch auction takes no reserve price or uniform pricing".to_string(),
+            ));
+        }
+    }
+
+    if let Some(sealed_bid) = &params.sealed_bid {
+        if params.dutch_auction.is_some() {
+            return Err(ContractError::GenericError(
+                "A Dutch auction takes no sealed bids".to_string(),
+            ));
+        }
+
+        if sealed_bid.reveal_duration == 0 {
+            return Err(ContractError::GenericError(
+                "Reveal duration must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if params.funding_goal == Some(0) {
+        return Err(ContractError::GenericError(
+            "Funding goal must be greater than 0".to_string(),
+        ));
+    }
+
+    // Dutch sales pay the influencer right away, so they can't be refunded
+    if params.funding_goal.is_some() && params.dutch_auction.is_some() {
+        return Err(ContractError::GenericError(
+            "A Dutch auction can't have a funding goal".to_string(),
+        ));
+    }
+
+    if let Some(retained) = &params.retained_allocation {
+        validate_retained_allocation(retained)?;
+    }
+
+    validate_allocation_caps(&stock, &params)?;
+
+    if let Some(presale) = &params.presale {
+        // The presale is for bidding
+        if params.dutch_auction.is_some() || params.sealed_bid.is_some() {
+            return Err(ContractError::GenericError(
+                "Only an auction taking open bids can have a presale".to_string(),
+            ));
+        }
+
+        parse_hash(&presale.merkle_root)?;
+
+        // The public phase has to start during the auction
+        if presale.public_start <= start_timestamp
+            || presale.public_start >= start_timestamp + duration
+        {
+            return Err(ContractError::GenericError(
+                "Public phase must start after the auction starts and before it ends".to_string(),
+            ));
+        }
+    }
+
+    stock.auction_start = Some(start_timestamp);
+    stock.reserve_price = params.reserve_price.unwrap_or(0);
+    stock.uniform_price = params.uniform_price.unwrap_or(false);
+    stock.dutch_auction = params.dutch_auction;
+    stock.sealed_bid = params.sealed_bid;
+    stock.funding_goal = params.funding_goal;
+    stock.max_shares_per_address = params.max_shares_per_address;
+    stock.min_shares_per_bid = params.min_shares_per_bid;
+    stock.presale = params.presale;
+    stock.retained_allocation = params.retained_allocation;
 
     // Mark as active auction
     stock.marked_as_active_auction = true;
 
-    // Calculate auction end time (24 hours later)
-    let end_timestamp = start_timestamp + (24 * 60 * 60 * 1000); // 24 hours in milliseconds
+    // Calculate auction end time
+    let end_timestamp = start_timestamp + duration;
     stock.auction_end = Some(end_timestamp);
-
-    // Save updated stock
-    STOCKS.save(deps.storage, &stock_id_bytes, &stock)?;
+    stock.max_auction_end = Some(end_timestamp + config.max_auction_extension);
 
     // Place inital bid with price set as 0
     let bid_id = BID_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
     BID_COUNT.save(deps.storage, &bid_id)?;
 
+    stock.opening_bid_id = Some(bid_id);
+
+    // Save updated stock
+    STOCKS.save(deps.storage, &stock_id_bytes, &stock)?;
+
     let bid = Bid {
         id: bid_id,
         stock_id,
         bidder: info.sender.clone(),
         price_per_share: 0,
-        shares_requested: stock.total_shares,
-        remaining_shares: stock.total_shares,
+        shares_requested: stock.auctioned_shares(),
+        remaining_shares: stock.auctioned_shares(),
         created_at: current_time,
-        open: 1,
+        open: true,
         active: true,
     };
 
@@ -129,118 +312,206 @@ pub fn start_auction(
         .add_attribute("action", "start_auction")
         .add_attribute("stock_id", stock_id.to_string())
         .add_attribute("auction_start", start_timestamp.to_string())
-        .add_attribute("auction_end", end_timestamp.to_string()))
+        .add_attribute("auction_end", end_timestamp.to_string())
+        .add_attribute("reserve_price", stock.reserve_price.to_string())
+        .add_attribute("uniform_price", stock.uniform_price.to_string())
+        .add_attribute("dutch_auction", stock.dutch_auction.is_some().to_string())
+        .add_attribute("sealed_bid", stock.sealed_bid.is_some().to_string())
+        .add_attribute(
+            "funding_goal",
+            stock
+                .funding_goal
+                .map_or("none".to_string(), |goal| goal.to_string()),
+        )
+        .add_attribute(
+            "max_shares_per_address",
+            stock
+                .max_shares_per_address
+                .map_or("none".to_string(), |shares| shares.to_string()),
+        )
+        .add_attribute(
+            "min_shares_per_bid",
+            stock
+                .min_shares_per_bid
+                .map_or("none".to_string(), |shares| shares.to_string()),
+        )
+        .add_attribute(
+            "public_start",
+            stock
+                .presale
+                .as_ref()
+                .map_or("none".to_string(), |presale| {
+                    presale.public_start.to_string()
+                }),
+        )
+        .add_attribute("retained_shares", stock.retained_shares().to_string()))
 }
 
-pub fn end_auction(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    stock_id: u64,
-) -> Result<Response, ContractError> {
-    let stock_id_bytes = stock_id.to_be_bytes();
+fn validate_retained_allocation(retained: &RetainedAllocation) -> Result<(), ContractError> {
+    // Some of the supply has to go up for auction
+    if retained.retained_bps == 0 || retained.retained_bps >= MAX_FEE_BPS {
+        return Err(ContractError::GenericError(f!(
+            "Retained allocation must be between 1 and {} basis points",
+            MAX_FEE_BPS - 1
+        )));
+    }
 
-    let mut stock = STOCKS
-        .load(deps.storage, &stock_id_bytes)
-        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
+    if retained.vesting_duration == 0 || retained.cliff > retained.vesting_duration {
+        return Err(ContractError::GenericError(
+            "Vesting duration must be greater than 0 and at least the cliff".to_string(),
+        ));
+    }
 
-    // Get contract state to check for owner
-    let state = STATE.load(deps.storage)?;
+    Ok(())
+}
 
-    // Authorization check - allow both the owner and the influencer to end auction
-    let is_owner = state.owner == info.sender;
-    let is_influencer = stock.influencer == info.sender;
+fn validate_allocation_caps(stock: &Stock, params: &AuctionParams) -> Result<(), ContractError> {
+    if params.max_shares_per_address.is_none() && params.min_shares_per_bid.is_none() {
+        return Ok(());
+    }
 
-    if !is_owner && !is_influencer {
-        return Err(ContractError::Unauthorized);
+    // Sealed bids don't show their shares until the auction is over
+    if params.sealed_bid.is_some() {
+        return Err(ContractError::GenericError(
+            "A sealed bid auction takes no allocation caps".to_string(),
+        ));
     }
 
-    // Get current blockchain time in milliseconds
-    let current_time = env.block.time.nanos() / 1_000_000; // Convert nanos to millis
+    let auctioned_shares = stock.total_shares
+        - params
+            .retained_allocation
+            .as_ref()
+            .map_or(0, |retained| retained.shares_of(stock.total_shares));
 
-    // Check if auction is ended and stock is in sale
-    // and marked as inactive
-    if let Some(end_timestamp) = stock.auction_end {
-        if current_time > end_timestamp && !stock.marked_as_active_auction {
-            return Err(ContractError::GenericError(f!(
-                "Stock has already been auctioned and in sale"
-            )));
-        }
-    }
+    let max_shares = params.max_shares_per_address.unwrap_or(auctioned_shares);
+    let min_shares = params.min_shares_per_bid.unwrap_or(1);
 
-    // Check stock hasn't started auction
-    if stock.auction_start.is_none() {
+    if max_shares == 0 || max_shares > auctioned_shares {
         return Err(ContractError::GenericError(f!(
-            "Stock is yet to be auctioned"
+            "Max shares per address must be between 1 and {auctioned_shares}"
         )));
     }
 
-    // Update auction end time to current block time
-    // if less than current time
-    if let Some(end_timestamp) = stock.auction_end {
-        if current_time < end_timestamp {
-            stock.auction_end = Some(current_time);
-        }
+    if min_shares == 0 || min_shares > max_shares {
+        return Err(ContractError::GenericError(f!(
+            "Min shares per bid must be between 1 and {max_shares}"
+        )));
     }
 
-    // mark as inactive auction
-    stock.marked_as_active_auction = false;
+    Ok(())
+}
 
-    // End the auction
-    STOCKS.save(deps.storage, &stock_id_bytes, &stock)?;
+pub fn end_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
 
-    // Create stakes from winning (open) bids
-    let open_bids = query::bids::get_open_bids_by_stock(deps.as_ref(), env.clone(), stock_id)?.bids;
+    let mut stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
 
-    for mut bid in open_bids {
-        // Create a stake from bid
-        let share_id = SHARE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
-        SHARE_COUNT.save(deps.storage, &share_id)?;
+    // Get current blockchain time in milliseconds
+    let current_time = env.block.time.nanos() / 1_000_000; // Convert nanos to millis
 
-        let share = Share {
-            id: share_id,
-            stock_id,
-            no_of_shares: bid.remaining_shares,
-            owner: bid.bidder.clone(),
-        };
+    start_settlement(deps.storage, current_time, &info.sender, &mut stock)?;
 
-        SHARES.save(deps.storage, &share_id.to_be_bytes(), &share)?;
+    // Settle what fits in one call, SettleAuction takes care of the rest
+    let batch = settle_batch(
+        deps.storage,
+        &config,
+        current_time,
+        &info.sender,
+        &mut stock,
+        DEFAULT_SETTLEMENT_LIMIT,
+    )?;
 
-        // close bid
-        bid.open = 0;
+    let is_influencer = stock.influencer == info.sender;
 
-        BIDS.save(deps.storage, &bid.id.to_be_bytes(), &bid)?;
+    Ok(Response::new()
+        .add_attribute("action", "end_auction")
+        .add_attribute("stock_id", stock_id.to_string())
+        .add_attribute("ended_by", info.sender.to_string())
+        .add_attribute("ended_at", current_time.to_string())
+        .add_attribute("is_influencer", is_influencer.to_string())
+        .add_attribute("settled_bids", batch.settled_bids.to_string())
+        .add_attribute("allocated_bids", batch.allocated_bids.to_string())
+        .add_attribute("complete", batch.complete.to_string())
+        .add_attribute("proceeds", batch.proceeds.to_string())
+        .add_attribute("fee", batch.fee.to_string())
+        .add_attribute("keeper_reward", batch.keeper_reward.to_string())
+        .add_attribute("refunds", batch.refunds.to_string())
+        .add_attribute("goal_missed", batch.goal_missed.to_string())
+        .add_messages(batch.messages))
+}
 
-        // Create Sale record
-        let sale_id = SALE_COUNT.may_load(deps.storage)?.unwrap_or(0) + 1;
-        SALE_COUNT.save(deps.storage, &sale_id)?;
+// Stop a stock from trading for good. Open orders can still be cancelled.
+pub fn delist_stock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Operator, &info.sender)?;
 
-        let sale = Sale {
-            id: sale_id,
-            stock_id,
-            no_of_shares: bid.remaining_shares,
-            price_per_share: bid.price_per_share,
-            from: stock.influencer.clone(),
-            to: bid.bidder.clone(),
-            created_at: current_time,
-        };
+    let mut stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
 
-        SALES.save(deps.storage, &sale_id.to_be_bytes(), &sale)?;
+    if stock.delisted_at.is_some() {
+        return Err(ContractError::GenericError(f!(
+            "Stock {stock_id} is already delisted"
+        )));
     }
 
-    // Make all bids for the stock inactive
-    let all_bids = query::bids::get_bids_by_stock_id(deps.as_ref(), env.clone(), stock_id)?.bids;
+    // Bids in a running auction are only settled by end_auction
+    if stock.auction_start.is_some() && stock.marked_as_active_auction {
+        return Err(ContractError::GenericError(f!(
+            "End the auction of stock {stock_id} before delisting it"
+        )));
+    }
 
-    for mut bid in all_bids {
-        bid.active = false;
+    let delisted_at = env.block.time.nanos() / 1_000_000;
+    stock.delisted_at = Some(delisted_at);
 
-        BIDS.save(deps.storage, &bid.id.to_be_bytes(), &bid)?;
+    STOCKS.save(deps.storage, &stock_id.to_be_bytes(), &stock)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "delist_stock")
+        .add_event(
+            Event::new("delist")
+                .add_attribute("stock_id", stock_id.to_string())
+                .add_attribute("ticker", stock.ticker)
+                .add_attribute("delisted_at", delisted_at.to_string())
+                .add_attribute("delisted_by", info.sender),
+        ))
+}
+
+// Replace a stock's metadata, only its influencer can
+pub fn update_stock_metadata(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+    metadata: StockMetadata,
+) -> Result<Response, ContractError> {
+    let mut stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Stock with id {stock_id}")))?;
+
+    if stock.influencer != info.sender {
+        return Err(ContractError::Unauthorized);
     }
 
+    validate_metadata(&metadata)?;
+
+    stock.metadata = metadata;
+    STOCKS.save(deps.storage, &stock_id.to_be_bytes(), &stock)?;
+
     Ok(Response::new()
-        .add_attribute("action", "end_auction")
+        .add_attribute("action", "update_stock_metadata")
         .add_attribute("stock_id", stock_id.to_string())
-        .add_attribute("ended_by", info.sender.to_string())
-        .add_attribute("ended_at", current_time.to_string())
-        .add_attribute("is_influencer", is_influencer.to_string()))
+        .add_event(metadata_event(stock_id, &stock.metadata)))
 }
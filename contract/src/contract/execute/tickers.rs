@@ -0,0 +1,109 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Storage};
+
+use crate::{
+    contract::{
+        access::ensure_owner,
+        tickers::{normalize_ticker, validate_ticker},
+    },
+    state::{TickerReservation, RESERVED_TICKERS, STOCKS, TICKERS},
+    ContractError,
+};
+
+use format as f;
+
+// Take a ticker away from the stock using it, which has to be delisted.
+// Returns the id of the stock that held it, if any.
+fn free_ticker(storage: &mut dyn Storage, ticker: &str) -> Result<Option<u64>, ContractError> {
+    let Some(stock_id) = TICKERS.may_load(storage, ticker)? else {
+        return Ok(None);
+    };
+
+    let stock = STOCKS.load(storage, &stock_id.to_be_bytes())?;
+
+    if stock.delisted_at.is_none() {
+        return Err(ContractError::GenericError(f!(
+            "Ticker {ticker} is used by stock {stock_id}, which is still listed"
+        )));
+    }
+
+    TICKERS.remove(storage, ticker);
+
+    Ok(Some(stock_id))
+}
+
+// Reserve a ticker, for a single holder when one is given or for nobody otherwise.
+// A ticker held by a delisted stock is taken from it, which lets it be reassigned.
+pub fn reserve_ticker(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    ticker: String,
+    holder: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let ticker = normalize_ticker(&ticker);
+    validate_ticker(&ticker)?;
+
+    let holder = holder
+        .map(|holder| deps.api.addr_validate(&holder))
+        .transpose()?;
+
+    let freed_from = free_ticker(deps.storage, &ticker)?;
+
+    RESERVED_TICKERS.save(
+        deps.storage,
+        &ticker,
+        &TickerReservation {
+            holder: holder.clone(),
+        },
+    )?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "reserve_ticker")
+        .add_attribute("ticker", ticker);
+
+    if let Some(holder) = holder {
+        res = res.add_attribute("holder", holder);
+    }
+
+    if let Some(stock_id) = freed_from {
+        res = res.add_attribute("freed_from", stock_id.to_string());
+    }
+
+    Ok(res)
+}
+
+// Make a ticker available to anyone again, dropping its reservation
+// or taking it from the delisted stock that uses it
+pub fn release_ticker(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    ticker: String,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let ticker = normalize_ticker(&ticker);
+
+    let reserved = RESERVED_TICKERS.has(deps.storage, &ticker);
+    let freed_from = free_ticker(deps.storage, &ticker)?;
+
+    if !reserved && freed_from.is_none() {
+        return Err(ContractError::GenericError(f!(
+            "Ticker {ticker} is neither reserved nor in use"
+        )));
+    }
+
+    RESERVED_TICKERS.remove(deps.storage, &ticker);
+
+    let mut res = Response::new()
+        .add_attribute("action", "release_ticker")
+        .add_attribute("ticker", ticker);
+
+    if let Some(stock_id) = freed_from {
+        res = res.add_attribute("freed_from", stock_id.to_string());
+    }
+
+    Ok(res)
+}
@@ -0,0 +1,68 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+
+use crate::{
+    contract::positions::credit_shares,
+    state::{Stock, Vesting, VESTINGS},
+    ContractError,
+};
+
+use format as f;
+
+// Hold the influencer's retained shares under vesting once the auction is settled
+pub fn start_vesting(storage: &mut dyn Storage, stock: &Stock, current_time: u64) -> StdResult<()> {
+    let Some(retained) = &stock.retained_allocation else {
+        return Ok(());
+    };
+
+    let vesting = Vesting {
+        stock_id: stock.id,
+        beneficiary: stock.influencer.clone(),
+        total_shares: stock.retained_shares(),
+        claimed_shares: 0,
+        start: current_time,
+        cliff: retained.cliff,
+        duration: retained.vesting_duration,
+    };
+
+    VESTINGS.save(storage, &stock.id.to_be_bytes(), &vesting)
+}
+
+pub fn claim_vested(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stock_id: u64,
+) -> Result<Response, ContractError> {
+    let mut vesting = VESTINGS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| ContractError::NotFound(f!("Vesting of stock {stock_id}")))?;
+
+    if vesting.beneficiary != info.sender {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let current_time = env.block.time.nanos() / 1_000_000;
+    let claimable = vesting.vested_at(current_time) - vesting.claimed_shares;
+
+    if claimable == 0 {
+        return Err(ContractError::GenericError(
+            "No vested shares to claim".to_string(),
+        ));
+    }
+
+    // Claimed shares join the influencer's position and can be sold like any other
+    credit_shares(deps.storage, stock_id, &info.sender, claimable)?;
+
+    vesting.claimed_shares += claimable;
+    VESTINGS.save(deps.storage, &stock_id.to_be_bytes(), &vesting)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_vested")
+        .add_attribute("stock_id", stock_id.to_string())
+        .add_attribute("beneficiary", info.sender.to_string())
+        .add_attribute("claimed_shares", claimable.to_string())
+        .add_attribute(
+            "locked_shares",
+            (vesting.total_shares - vesting.claimed_shares).to_string(),
+        ))
+}
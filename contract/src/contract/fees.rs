@@ -0,0 +1,69 @@
+use cosmwasm_std::{Addr, StdResult, Storage};
+
+use crate::state::{Config, Stock, ACCRUED_FEES, ROYALTIES};
+
+// 100% in basis points
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+// Portion of `amount` owed at `fee_bps`, rounded down
+pub fn calculate_fee(amount: u128, fee_bps: u16) -> u128 {
+    amount * fee_bps as u128 / MAX_FEE_BPS as u128
+}
+
+// Take the protocol fee on a sale worth `amount` and add it to the accrued fees.
+// Returns the fee, which the caller deducts from the seller's proceeds.
+pub fn take_protocol_fee(
+    storage: &mut dyn Storage,
+    config: &Config,
+    amount: u128,
+) -> StdResult<u128> {
+    let fee = calculate_fee(amount, config.fee_bps);
+
+    if fee > 0 {
+        let accrued = ACCRUED_FEES.may_load(storage)?.unwrap_or(0);
+        ACCRUED_FEES.save(storage, &(accrued + fee))?;
+    }
+
+    Ok(fee)
+}
+
+// Take the influencer's royalty on a secondary sale worth `amount`
+// and add it to the stock's lifetime total.
+// Returns the royalty, which the caller deducts from the seller's proceeds
+// and pays to `royalty_recipient`. The influencer pays no royalty to themselves.
+pub fn take_royalty(
+    storage: &mut dyn Storage,
+    config: &Config,
+    stock: &Stock,
+    seller: &Addr,
+    amount: u128,
+) -> StdResult<u128> {
+    if *seller == stock.influencer {
+        return Ok(0);
+    }
+
+    let key = stock.id.to_be_bytes();
+
+    let Some(mut royalty) = ROYALTIES.may_load(storage, &key)? else {
+        return Ok(0);
+    };
+
+    // A lowered ceiling applies to royalties set before it
+    let royalty_bps = std::cmp::min(royalty.royalty_bps, config.max_royalty_bps);
+    let amount = calculate_fee(amount, royalty_bps);
+
+    if amount > 0 {
+        royalty.total_paid += amount;
+        ROYALTIES.save(storage, &key, &royalty)?;
+    }
+
+    Ok(amount)
+}
+
+// Where a stock's royalties are paid
+pub fn royalty_recipient(storage: &dyn Storage, stock: &Stock) -> StdResult<Addr> {
+    Ok(ROYALTIES
+        .may_load(storage, &stock.id.to_be_bytes())?
+        .and_then(|royalty| royalty.payout_address)
+        .unwrap_or(stock.influencer.clone()))
+}
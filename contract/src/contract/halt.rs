@@ -0,0 +1,38 @@
+use cosmwasm_std::Storage;
+
+use crate::{
+    state::{GLOBAL_HALT, STOCKS, STOCK_HALTS},
+    ContractError,
+};
+
+use format as f;
+
+// Fails if trading is halted globally or for the stock, or the stock is delisted.
+// Only new bids and orders are blocked, cancels still go through.
+pub fn ensure_not_halted(storage: &dyn Storage, stock_id: u64) -> Result<(), ContractError> {
+    if let Some(halt) = GLOBAL_HALT.may_load(storage)? {
+        return Err(ContractError::GenericError(f!(
+            "Trading is halted: {}",
+            halt.reason
+        )));
+    }
+
+    if let Some(halt) = STOCK_HALTS.may_load(storage, &stock_id.to_be_bytes())? {
+        return Err(ContractError::GenericError(f!(
+            "Trading of stock {stock_id} is halted: {}",
+            halt.reason
+        )));
+    }
+
+    let delisted = STOCKS
+        .may_load(storage, &stock_id.to_be_bytes())?
+        .is_some_and(|stock| stock.delisted_at.is_some());
+
+    if delisted {
+        return Err(ContractError::GenericError(f!(
+            "Stock {stock_id} is delisted"
+        )));
+    }
+
+    Ok(())
+}
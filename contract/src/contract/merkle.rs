@@ -0,0 +1,41 @@
+use cosmwasm_std::Addr;
+use sha2::{Digest, Sha256};
+
+use crate::ContractError;
+
+use format as f;
+
+// Leaf of a presale allow list, the sha256 of "{address}:{allocation}"
+pub fn presale_leaf(address: &Addr, allocation: u64) -> [u8; 32] {
+    Sha256::digest(f!("{address}:{allocation}").as_bytes()).into()
+}
+
+// Parent of two nodes, the sha256 of the pair with the smaller one first
+// so proofs don't have to say which side each node is on
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+
+    Sha256::new()
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+pub fn parse_hash(hash: &str) -> Result<[u8; 32], ContractError> {
+    hex::decode(hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ContractError::GenericError(f!("{hash} is not a hex encoded sha256 hash")))
+}
+
+// Whether the hex encoded proof leads from the leaf to the hex encoded root
+pub fn verify_proof(root: &str, leaf: [u8; 32], proof: &[String]) -> Result<bool, ContractError> {
+    let root = parse_hash(root)?;
+
+    let computed = proof.iter().try_fold(leaf, |node, sibling| {
+        parse_hash(sibling).map(|sibling| hash_pair(&node, &sibling))
+    })?;
+
+    Ok(computed == root)
+}
@@ -0,0 +1,102 @@
+use cosmwasm_std::Event;
+
+use crate::{state::StockMetadata, ContractError};
+
+use format as f;
+
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+pub const MAX_DESCRIPTION_LENGTH: usize = 1_000;
+pub const MAX_IMAGE_URI_LENGTH: usize = 512;
+pub const MAX_SOCIALS: usize = 8;
+pub const MAX_SOCIAL_LENGTH: usize = 64;
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_LENGTH: usize = 32;
+
+const IMAGE_URI_SCHEMES: [&str; 2] = ["https://", "ipfs://"];
+
+fn validate_text(field: &str, value: &str, max_length: usize) -> Result<(), ContractError> {
+    if value.trim().is_empty() {
+        return Err(ContractError::GenericError(f!("{field} cannot be empty")));
+    }
+
+    if value.chars().count() > max_length {
+        return Err(ContractError::GenericError(f!(
+            "{field} cannot be longer than {max_length} characters"
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn validate_metadata(metadata: &StockMetadata) -> Result<(), ContractError> {
+    if let Some(display_name) = &metadata.display_name {
+        validate_text("Display name", display_name, MAX_DISPLAY_NAME_LENGTH)?;
+    }
+
+    if let Some(description) = &metadata.description {
+        validate_text("Description", description, MAX_DESCRIPTION_LENGTH)?;
+    }
+
+    if let Some(image_uri) = &metadata.image_uri {
+        validate_text("Image URI", image_uri, MAX_IMAGE_URI_LENGTH)?;
+
+        if !IMAGE_URI_SCHEMES
+            .iter()
+            .any(|scheme| image_uri.starts_with(scheme))
+        {
+            return Err(ContractError::GenericError(
+                "Image URI must start with https:// or ipfs://".to_string(),
+            ));
+        }
+    }
+
+    if metadata.socials.len() > MAX_SOCIALS {
+        return Err(ContractError::GenericError(f!(
+            "A stock can list at most {MAX_SOCIALS} social handles"
+        )));
+    }
+
+    for social in &metadata.socials {
+        validate_text("Social platform", &social.platform, MAX_SOCIAL_LENGTH)?;
+        validate_text("Social handle", &social.handle, MAX_SOCIAL_LENGTH)?;
+    }
+
+    if metadata.tags.len() > MAX_TAGS {
+        return Err(ContractError::GenericError(f!(
+            "A stock can have at most {MAX_TAGS} tags"
+        )));
+    }
+
+    for tag in &metadata.tags {
+        validate_text("Tag", tag, MAX_TAG_LENGTH)?;
+    }
+
+    Ok(())
+}
+
+// Emitted whenever a stock's metadata is set
+pub fn metadata_event(stock_id: u64, metadata: &StockMetadata) -> Event {
+    let mut event = Event::new("stock_metadata").add_attribute("stock_id", stock_id.to_string());
+
+    if let Some(display_name) = &metadata.display_name {
+        event = event.add_attribute("display_name", display_name);
+    }
+
+    if let Some(description) = &metadata.description {
+        event = event.add_attribute("description", description);
+    }
+
+    if let Some(image_uri) = &metadata.image_uri {
+        event = event.add_attribute("image_uri", image_uri);
+    }
+
+    for social in &metadata.socials {
+        event = event.add_attribute("social", f!("{}:{}", social.platform, social.handle));
+    }
+
+    for tag in &metadata.tags {
+        event = event.add_attribute("tag", tag);
+    }
+
+    event
+}
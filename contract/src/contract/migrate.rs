@@ -0,0 +1,313 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::escrow::deposit_buy_order_escrow;
+use crate::contract::execute::stocks::{AUCTION_DURATION, TOTAL_SHARES};
+use crate::contract::order_book::{add_open_buy_order, add_open_sell_order};
+use crate::contract::positions::{load_position, lock_shares};
+use crate::contract::query::bids::MIN_BID_INCREMENT;
+use crate::contract::tickers::normalize_ticker;
+use crate::contract::DENOM;
+use crate::state::{
+    Bid, Config, BIDS, BUY_ORDERS, BUY_ORDER_ESCROWS, SELL_ORDERS, SHARES, STOCKS, TICKERS,
+};
+use crate::ContractError;
+
+use format as f;
+
+// Share record as stored before positions were keyed by (stock_id, owner),
+// one was created per winning bid and per first purchase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LegacyShare {
+    pub id: u64,
+    pub stock_id: u64,
+    pub no_of_shares: u64,
+    pub owner: Addr,
+}
+
+pub struct LegacyShareIndexes<'a> {
+    pub stock_id: MultiIndex<'a, u64, LegacyShare, &'a [u8]>,
+    pub owner: MultiIndex<'a, Addr, LegacyShare, &'a [u8]>,
+}
+
+impl IndexList<LegacyShare> for LegacyShareIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<LegacyShare>> + '_> {
+        let v = vec![
+            &self.stock_id as &dyn Index<LegacyShare>,
+            &self.owner as &dyn Index<LegacyShare>,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const LEGACY_SHARE_INDEXES: LegacyShareIndexes = LegacyShareIndexes {
+    stock_id: MultiIndex::new(|_pk, share| share.stock_id, "share", "share__stock_id"),
+    owner: MultiIndex::new(|_pk, share| share.owner.clone(), "share", "share__owner"),
+};
+
+pub const LEGACY_SHARES: IndexedMap<&[u8], LegacyShare, LegacyShareIndexes> =
+    IndexedMap::new("share", LEGACY_SHARE_INDEXES);
+pub const LEGACY_SHARE_COUNT: Item<u64> = Item::new("share_count");
+
+// Fold every legacy share record into the owner's position for that stock.
+// Returns the number of legacy records that were merged.
+pub fn merge_share_records(storage: &mut dyn Storage) -> StdResult<u64> {
+    let legacy_shares = LEGACY_SHARES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, share)| share))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for legacy in &legacy_shares {
+        let mut share = load_position(storage, legacy.stock_id, &legacy.owner)?;
+
+        share.no_of_shares += legacy.no_of_shares;
+
+        if share.no_of_shares > 0 {
+            SHARES.save(storage, (legacy.stock_id, &legacy.owner), &share)?;
+        }
+
+        LEGACY_SHARES.remove(storage, &legacy.id.to_be_bytes())?;
+    }
+
+    LEGACY_SHARE_COUNT.remove(storage);
+
+    Ok(legacy_shares.len() as u64)
+}
+
+// Lock the unsold shares of every open sell order, which wasn't done before.
+// Orders were only checked against the owner's shares when they were placed, so the
+// owner may no longer hold enough for all of them. Older orders are covered first,
+// an order that can only be partly covered is cut down to what is, and one that
+// can't be covered at all is cancelled.
+// Returns the number of orders that were locked and the number that were cut down or cancelled.
+pub fn lock_open_sell_orders(
+    storage: &mut dyn Storage,
+    current_time: u64,
+) -> Result<(u64, u64), ContractError> {
+    let open_sell_orders = SELL_ORDERS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, order)| order))
+        .filter(|order| order.as_ref().map_or(true, |o| o.resolved_at.is_none()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut locked = 0;
+    let mut capped = 0;
+
+    for mut order in open_sell_orders {
+        let unsold_shares = order.available_shares - order.sold_shares;
+        let held_shares = load_position(storage, order.stock_id, &order.owner)?.available_shares();
+        let covered_shares = unsold_shares.min(held_shares);
+
+        if covered_shares < unsold_shares {
+            order.available_shares = order.sold_shares + covered_shares;
+
+            if covered_shares == 0 {
+                order.resolved_at = Some(current_time);
+            }
+
+            SELL_ORDERS.save(storage, &order.id.to_be_bytes(), &order)?;
+            capped += 1;
+        }
+
+        if covered_shares > 0 {
+            lock_shares(storage, order.stock_id, &order.owner, covered_shares)?;
+            locked += 1;
+        }
+    }
+
+    Ok((locked, capped))
+}
+
+// Build the price-indexed order book from the orders that are still open.
+// Returns the number of orders that were indexed.
+pub fn index_open_orders(storage: &mut dyn Storage) -> StdResult<u64> {
+    let open_sell_orders = SELL_ORDERS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, order)| order))
+        .filter(|order| order.as_ref().map_or(true, |o| o.resolved_at.is_none()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let open_buy_orders = BUY_ORDERS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, order)| order))
+        .filter(|order| order.as_ref().map_or(true, |o| o.resolved_at.is_none()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for order in &open_sell_orders {
+        add_open_sell_order(storage, order)?;
+    }
+
+    for order in &open_buy_orders {
+        add_open_buy_order(storage, order)?;
+    }
+
+    Ok((open_sell_orders.len() + open_buy_orders.len()) as u64)
+}
+
+// The values the contract ran with before they became configurable
+pub fn default_config() -> Config {
+    Config {
+        denom: DENOM.to_string(),
+        total_shares: TOTAL_SHARES,
+        min_total_shares: TOTAL_SHARES,
+        max_total_shares: TOTAL_SHARES,
+        auction_duration: AUCTION_DURATION,
+        min_auction_duration: AUCTION_DURATION,
+        max_auction_duration: AUCTION_DURATION,
+        min_bid_increment: MIN_BID_INCREMENT,
+        soft_close_window: 0,
+        soft_close_extension: 0,
+        max_auction_extension: 0,
+        bid_lock_window: 0,
+        fee_bps: 0,
+        fee_collector: None,
+        keeper_reward_bps: 0,
+        max_royalty_bps: 0,
+        unrevealed_slash_bps: 0,
+    }
+}
+
+// Fund an escrow entry for every open buy order placed before buy order
+// funds were tracked. The contract already holds these funds.
+// Returns the number of orders that were backfilled.
+pub fn backfill_buy_order_escrows(storage: &mut dyn Storage) -> Result<u64, ContractError> {
+    let open_buy_orders = BUY_ORDERS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, order)| order))
+        .filter(|order| order.as_ref().map_or(true, |o| o.resolved_at.is_none()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut backfilled = 0;
+
+    for order in &open_buy_orders {
+        if BUY_ORDER_ESCROWS.has(storage, &order.id.to_be_bytes()) {
+            continue;
+        }
+
+        let remaining_shares = order.requested_shares - order.bought_shares;
+        let amount = order.price_per_share * remaining_shares as u128;
+
+        deposit_buy_order_escrow(storage, order.id, amount)?;
+        backfilled += 1;
+    }
+
+    Ok(backfilled)
+}
+
+// Bid as stored before `open` became a bool
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LegacyBid {
+    pub id: u64,
+    pub stock_id: u64,
+    pub bidder: Addr,
+    pub price_per_share: u128,
+    pub shares_requested: u64,
+    pub remaining_shares: u64,
+    pub created_at: u64,
+    pub open: u8,
+    pub active: bool,
+}
+
+pub struct LegacyBidIndexes<'a> {
+    pub stock_open: MultiIndex<'a, (u64, u8), LegacyBid, &'a [u8]>,
+}
+
+impl IndexList<LegacyBid> for LegacyBidIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<LegacyBid>> + '_> {
+        let v = vec![&self.stock_open as &dyn Index<LegacyBid>];
+        Box::new(v.into_iter())
+    }
+}
+
+// Only the index that was replaced, the other bid indexes keep their keys
+pub const LEGACY_BID_INDEXES: LegacyBidIndexes = LegacyBidIndexes {
+    stock_open: MultiIndex::new(
+        |_pk, bid| (bid.stock_id, bid.open),
+        "bids",
+        "bids__stock_open",
+    ),
+};
+
+pub const LEGACY_BIDS: IndexedMap<&[u8], LegacyBid, LegacyBidIndexes> =
+    IndexedMap::new("bids", LEGACY_BID_INDEXES);
+
+// Rewrite every bid with `open` as a bool, indexing the open ones by price.
+// Returns the number of bids that were converted.
+pub fn convert_bid_open_flags(storage: &mut dyn Storage) -> StdResult<u64> {
+    let legacy_bids = LEGACY_BIDS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, bid)| bid))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for legacy in &legacy_bids {
+        let bid = Bid {
+            id: legacy.id,
+            stock_id: legacy.stock_id,
+            bidder: legacy.bidder.clone(),
+            price_per_share: legacy.price_per_share,
+            shares_requested: legacy.shares_requested,
+            remaining_shares: legacy.remaining_shares,
+            created_at: legacy.created_at,
+            open: legacy.open != 0,
+            active: legacy.active,
+        };
+
+        // Drop the record with its entry in the old (stock_id, open) index,
+        // saving it again fills in the price-ordered one
+        let pk = bid.id.to_be_bytes();
+        LEGACY_BIDS.remove(storage, &pk)?;
+        BIDS.save(storage, &pk, &bid)?;
+    }
+
+    Ok(legacy_bids.len() as u64)
+}
+
+// Auctions run before bid funds were escrowed paid the influencer as bids came in,
+// so their refunds can't be paid out of an auction escrow
+pub fn ensure_no_running_auctions(storage: &dyn Storage) -> Result<(), ContractError> {
+    let running = STOCKS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, stock)| stock))
+        .find(|stock| {
+            stock.as_ref().map_or(true, |s| {
+                s.auction_start.is_some() && s.marked_as_active_auction
+            })
+        })
+        .transpose()?;
+
+    if let Some(stock) = running {
+        return Err(ContractError::GenericError(f!(
+            "End the auction of stock {} before migrating",
+            stock.id
+        )));
+    }
+
+    Ok(())
+}
+
+// Register the normalized ticker of every stock. Tickers weren't unique before,
+// so when several stocks share one it goes to the oldest of them.
+// Returns the number of tickers that were registered.
+pub fn index_tickers(storage: &mut dyn Storage) -> StdResult<u64> {
+    let stocks = STOCKS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, stock)| stock))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut indexed = 0;
+
+    for stock in &stocks {
+        let ticker = normalize_ticker(&stock.ticker);
+
+        if ticker.is_empty() || TICKERS.has(storage, &ticker) {
+            continue;
+        }
+
+        TICKERS.save(storage, &ticker, &stock.id)?;
+        indexed += 1;
+    }
+
+    Ok(indexed)
+}
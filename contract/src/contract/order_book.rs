@@ -0,0 +1,95 @@
+use cosmwasm_std::{Order, StdResult, Storage};
+
+use crate::state::{
+    BuyOrder, SellOrder, BUY_ORDERS, OPEN_BUY_ORDERS, OPEN_SELL_ORDERS, SELL_ORDERS,
+};
+
+// Buy orders are indexed by inverted price so the highest price sorts first
+fn buy_price_key(price_per_share: u128) -> u128 {
+    u128::MAX - price_per_share
+}
+
+pub fn add_open_sell_order(storage: &mut dyn Storage, order: &SellOrder) -> StdResult<()> {
+    OPEN_SELL_ORDERS.save(
+        storage,
+        (order.stock_id, order.price_per_share, order.id),
+        &(),
+    )
+}
+
+pub fn remove_open_sell_order(storage: &mut dyn Storage, order: &SellOrder) {
+    OPEN_SELL_ORDERS.remove(storage, (order.stock_id, order.price_per_share, order.id));
+}
+
+pub fn add_open_buy_order(storage: &mut dyn Storage, order: &BuyOrder) -> StdResult<()> {
+    OPEN_BUY_ORDERS.save(
+        storage,
+        (
+            order.stock_id,
+            buy_price_key(order.price_per_share),
+            order.id,
+        ),
+        &(),
+    )
+}
+
+pub fn remove_open_buy_order(storage: &mut dyn Storage, order: &BuyOrder) {
+    OPEN_BUY_ORDERS.remove(
+        storage,
+        (
+            order.stock_id,
+            buy_price_key(order.price_per_share),
+            order.id,
+        ),
+    );
+}
+
+// Open sell orders for a stock, lazily loaded from the cheapest up
+// (or from the most expensive down when `order` is descending)
+pub fn sell_orders_by_price<'a>(
+    storage: &'a dyn Storage,
+    stock_id: u64,
+    order: Order,
+) -> Box<dyn Iterator<Item = StdResult<SellOrder>> + 'a> {
+    Box::new(
+        OPEN_SELL_ORDERS
+            .sub_prefix(stock_id)
+            .keys(storage, None, None, order)
+            .map(move |key| {
+                let (_, order_id) = key?;
+                SELL_ORDERS.load(storage, &order_id.to_be_bytes())
+            }),
+    )
+}
+
+// Open buy orders for a stock, lazily loaded from the highest price down
+// (or from the lowest up when `order` is descending)
+pub fn buy_orders_by_price<'a>(
+    storage: &'a dyn Storage,
+    stock_id: u64,
+    order: Order,
+) -> Box<dyn Iterator<Item = StdResult<BuyOrder>> + 'a> {
+    Box::new(
+        OPEN_BUY_ORDERS
+            .sub_prefix(stock_id)
+            .keys(storage, None, None, order)
+            .map(move |key| {
+                let (_, order_id) = key?;
+                BUY_ORDERS.load(storage, &order_id.to_be_bytes())
+            }),
+    )
+}
+
+// The cheapest open sell order for a stock
+pub fn best_sell_order(storage: &dyn Storage, stock_id: u64) -> StdResult<Option<SellOrder>> {
+    sell_orders_by_price(storage, stock_id, Order::Ascending)
+        .next()
+        .transpose()
+}
+
+// The highest priced open buy order for a stock
+pub fn best_buy_order(storage: &dyn Storage, stock_id: u64) -> StdResult<Option<BuyOrder>> {
+    buy_orders_by_price(storage, stock_id, Order::Ascending)
+        .next()
+        .transpose()
+}
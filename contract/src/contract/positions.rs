@@ -0,0 +1,127 @@
+use cosmwasm_std::{Addr, StdResult, Storage};
+
+use crate::{
+    state::{Share, SHARES},
+    ContractError,
+};
+
+use format as f;
+
+// Load an owner's position in a stock, empty if they hold none
+pub fn load_position(storage: &dyn Storage, stock_id: u64, owner: &Addr) -> StdResult<Share> {
+    Ok(SHARES
+        .may_load(storage, (stock_id, owner))?
+        .unwrap_or(Share {
+            stock_id,
+            no_of_shares: 0,
+            owner: owner.clone(),
+            locked_shares: 0,
+        }))
+}
+
+fn save_position(storage: &mut dyn Storage, share: &Share) -> StdResult<()> {
+    // Empty positions are dropped so they don't show up in queries
+    if share.no_of_shares == 0 {
+        SHARES.remove(storage, (share.stock_id, &share.owner))
+    } else {
+        SHARES.save(storage, (share.stock_id, &share.owner), share)
+    }
+}
+
+// Add shares to an owner's position
+pub fn credit_shares(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+    owner: &Addr,
+    amount: u64,
+) -> StdResult<()> {
+    let mut share = load_position(storage, stock_id, owner)?;
+    share.no_of_shares += amount;
+
+    save_position(storage, &share)
+}
+
+// Remove shares from the available part of an owner's position
+pub fn debit_shares(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+    owner: &Addr,
+    amount: u64,
+) -> Result<(), ContractError> {
+    let mut share = load_position(storage, stock_id, owner)?;
+
+    if share.available_shares() < amount {
+        return Err(ContractError::GenericError(f!(
+            "Insufficient shares. Has {} available, needs {amount}",
+            share.available_shares()
+        )));
+    }
+
+    share.no_of_shares -= amount;
+
+    Ok(save_position(storage, &share)?)
+}
+
+// Remove shares that were locked by a sell order that has been filled
+pub fn debit_locked_shares(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+    owner: &Addr,
+    amount: u64,
+) -> Result<(), ContractError> {
+    let mut share = load_position(storage, stock_id, owner)?;
+
+    if share.locked_shares < amount {
+        return Err(ContractError::GenericError(f!(
+            "Position has {} locked shares, cannot debit {amount}",
+            share.locked_shares
+        )));
+    }
+
+    share.locked_shares -= amount;
+    share.no_of_shares -= amount;
+
+    Ok(save_position(storage, &share)?)
+}
+
+// Reserve available shares for a sell order
+pub fn lock_shares(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+    owner: &Addr,
+    amount: u64,
+) -> Result<(), ContractError> {
+    let mut share = load_position(storage, stock_id, owner)?;
+
+    if share.available_shares() < amount {
+        return Err(ContractError::GenericError(f!(
+            "Insufficient shares. Has {} available, needs {amount}",
+            share.available_shares()
+        )));
+    }
+
+    share.locked_shares += amount;
+
+    Ok(save_position(storage, &share)?)
+}
+
+// Release shares reserved by a sell order
+pub fn unlock_shares(
+    storage: &mut dyn Storage,
+    stock_id: u64,
+    owner: &Addr,
+    amount: u64,
+) -> Result<(), ContractError> {
+    let mut share = load_position(storage, stock_id, owner)?;
+
+    if share.locked_shares < amount {
+        return Err(ContractError::GenericError(f!(
+            "Position has {} locked shares, cannot unlock {amount}",
+            share.locked_shares
+        )));
+    }
+
+    share.locked_shares -= amount;
+
+    Ok(save_position(storage, &share)?)
+}
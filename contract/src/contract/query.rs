@@ -1,5 +1,14 @@
 pub mod bids;
+pub mod config;
+pub mod dutch;
+pub mod escrow;
+pub mod fees;
+pub mod halt;
 pub mod orders;
+pub mod ownership;
+pub mod royalties;
 pub mod sales;
 pub mod shares;
 pub mod stocks;
+pub mod tickers;
+pub mod vesting;
@@ -1,8 +1,9 @@
-use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult};
+use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult, Storage};
 
 use crate::{
-    msg::{GetBidByIdResponse, GetBidsResponse, GetMinimumBidPriceResponse},
-    state::BIDS,
+    contract::positions::load_position,
+    msg::{GetBidByIdResponse, GetBidsResponse, GetMinimumBidPriceResponse, GetSealedBidResponse},
+    state::{Bid, Stock, BIDS, CONFIG, SEALED_BIDS, STOCKS},
 };
 
 use format as f;
@@ -15,6 +16,14 @@ pub fn get_bid_by_id(deps: Deps, _env: Env, bid_id: u64) -> StdResult<GetBidById
     Ok(GetBidByIdResponse { bid })
 }
 
+pub fn get_sealed_bid(deps: Deps, _env: Env, bid_id: u64) -> StdResult<GetSealedBidResponse> {
+    let sealed_bid = SEALED_BIDS
+        .load(deps.storage, &bid_id.to_be_bytes())
+        .map_err(|_| StdError::not_found(f!("Sealed bid with id {bid_id}")))?;
+
+    Ok(GetSealedBidResponse { sealed_bid })
+}
+
 pub fn get_bids_by_bidder(
     deps: Deps,
     _env: Env,
@@ -44,9 +53,9 @@ pub fn get_bids_by_bidder(
     // Filter by open
     if let Some(is_open) = is_open {
         if is_open {
-            bids = bids.into_iter().filter(|bid| bid.open == 1).collect();
+            bids.retain(|bid| bid.open);
         } else {
-            bids = bids.into_iter().filter(|bid| bid.open == 0).collect();
+            bids.retain(|bid| !bid.open);
         }
     }
 
@@ -65,43 +74,146 @@ pub fn get_open_bids_by_stock(deps: Deps, _env: Env, stock_id: u64) -> StdResult
     // This filters by stock(stock_id) and open(1)
     // while ordering by price in ascending order
 
-    let mut bids = BIDS
-        .idx
+    let bids = open_bids(deps.storage, stock_id).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(GetBidsResponse { bids })
+}
+
+// The stock's open bids, ordered by price (ascending)
+pub fn open_bids(
+    storage: &dyn Storage,
+    stock_id: u64,
+) -> impl Iterator<Item = StdResult<Bid>> + '_ {
+    BIDS.idx
         .stock_open
-        .prefix((stock_id, 1))
-        .range(deps.storage, None, None, Order::Ascending)
-        // Extract the stock data from each item.
-        .map(|item| item.and_then(|(_, bid)| Ok(bid)))
-        .collect::<Result<Vec<_>, _>>()?;
+        .sub_prefix((stock_id, 1))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, bid)| bid))
+}
 
-    // Then sort in memory by price
-    bids.sort_by(|a, b| a.price_per_share.cmp(&b.price_per_share)); // ascending order
+// The influencer's opening bid of the stock's current auction.
+// It holds the shares nobody has bid for, in a Dutch auction the ones still for sale.
+pub fn opening_bid(storage: &dyn Storage, stock: &Stock) -> StdResult<Option<Bid>> {
+    if let Some(bid_id) = stock.opening_bid_id {
+        return BIDS.may_load(storage, &bid_id.to_be_bytes());
+    }
 
-    Ok(GetBidsResponse { bids })
+    // Auctions started before it was recorded only ever had one, the first bid
+    BIDS.idx
+        .stock_id
+        .prefix(stock.id)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, bid)| bid))
+        .next()
+        .transpose()
+}
+
+// How many more shares the bidder may bid for on the stock, counting the shares of
+// their open bids in its current auction. None when the stock caps no one.
+pub fn remaining_allocation(
+    storage: &dyn Storage,
+    stock: &Stock,
+    bidder: &Addr,
+) -> StdResult<Option<u64>> {
+    let Some(max_shares) = stock.max_shares_per_address else {
+        return Ok(None);
+    };
+
+    let held_shares = held_shares(storage, stock, bidder)?;
+
+    Ok(Some(max_shares.saturating_sub(held_shares)))
+}
+
+// Shares of the bidder's open bids in the stock's current auction
+pub fn held_shares(storage: &dyn Storage, stock: &Stock, bidder: &Addr) -> StdResult<u64> {
+    // Dutch purchases are credited right away instead of staying bids
+    if stock.dutch_auction.is_some() {
+        return Ok(load_position(storage, stock.id, bidder)?.no_of_shares);
+    }
+
+    let mut held_shares = 0;
+
+    for bid in BIDS
+        .idx
+        .stock_bidder
+        .prefix((stock.id, bidder.clone()))
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (_, bid) = bid?;
+
+        if bid.open && stock.opening_bid_id != Some(bid.id) {
+            held_shares += bid.remaining_shares;
+        }
+    }
+
+    Ok(held_shares)
 }
 
-// Minimum bid increment (0.000001 uosmo)
-const MIN_BID_INCREMENT: u128 = 1;
+// Default minimum bid increment (0.000001 uosmo)
+pub const MIN_BID_INCREMENT: u128 = 1;
 
 // Function to get the minimum bid price for a given number of shares
 pub fn get_minimum_bid_price(
     deps: Deps,
-    env: Env,
+    _env: Env,
     stock_id: u64,
     shares_requested: u64,
+    bidder: Option<Addr>,
 ) -> StdResult<GetMinimumBidPriceResponse> {
+    let stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| StdError::not_found(f!("Stock with id {stock_id}")))?;
+
+    // The open bids only ever cover the auctioned supply
+    if shares_requested > stock.auctioned_shares() {
+        return Err(StdError::generic_err(f!(
+            "Cannot bid for more than the {} shares of stock {stock_id}",
+            stock.auctioned_shares()
+        )));
+    }
+
+    let min_price = outbid_price(deps.storage, &stock, shares_requested, None)?;
+
+    // Without a bidder, what a new one could bid for
+    let remaining_allocation = match bidder {
+        Some(bidder) => remaining_allocation(deps.storage, &stock, &bidder)?,
+        None => stock.max_shares_per_address,
+    };
+
+    Ok(GetMinimumBidPriceResponse {
+        min_price: min_price.to_string(),
+        shares_requested,
+        remaining_allocation,
+    })
+}
+
+// The lowest price that outbids the open bids for `shares_requested` shares of the stock,
+// leaving out `bid_id`. Walks the open bids the way process_outbids takes their shares.
+pub fn outbid_price(
+    storage: &dyn Storage,
+    stock: &Stock,
+    shares_requested: u64,
+    bid_id: Option<u64>,
+) -> StdResult<u128> {
+    let config = CONFIG.load(storage)?;
+
     // Default minimum price is 0
     let mut min_price: u128 = 0;
     let mut available_shares = shares_requested;
 
-    // Get all open bids for this stock, ordered by price (ascending)
-    let open_bids = get_open_bids_by_stock(deps, env, stock_id)?.bids;
+    // Start with loweset priced bids and work up,
+    // only reading as many as the requested shares reach
+    for bid in open_bids(storage, stock.id) {
+        let bid = bid?;
+
+        // A bid doesn't outbid itself
+        if Some(bid.id) == bid_id {
+            continue;
+        }
 
-    // Start with loweset priced bids and work up
-    for bid in open_bids {
         if available_shares <= bid.remaining_shares {
             // We need to outbid this price
-            min_price = bid.price_per_share + MIN_BID_INCREMENT;
+            min_price = bid.price_per_share + config.min_bid_increment;
             break;
         } else {
             // We can skip this bid and move to the next one
@@ -109,10 +221,8 @@ pub fn get_minimum_bid_price(
         }
     }
 
-    Ok(GetMinimumBidPriceResponse {
-        min_price: min_price.to_string(),
-        shares_requested,
-    })
+    // No bid may go below the reserve
+    Ok(min_price.max(stock.reserve_price))
 }
 
 pub fn get_bids_by_stock_id(deps: Deps, _env: Env, stock_id: u64) -> StdResult<GetBidsResponse> {
@@ -0,0 +1,9 @@
+use cosmwasm_std::{Deps, Env, StdResult};
+
+use crate::{msg::GetConfigResponse, state::CONFIG};
+
+pub fn get_config(deps: Deps, _env: Env) -> StdResult<GetConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(GetConfigResponse { config })
+}
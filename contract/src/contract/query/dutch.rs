@@ -0,0 +1,39 @@
+use cosmwasm_std::{Deps, Env, StdError, StdResult};
+
+use crate::{contract::query::bids::opening_bid, msg::GetDutchAuctionResponse, state::STOCKS};
+
+use format as f;
+
+pub fn get_dutch_auction(
+    deps: Deps,
+    env: Env,
+    stock_id: u64,
+) -> StdResult<GetDutchAuctionResponse> {
+    let stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| StdError::not_found(f!("Stock with id {stock_id}")))?;
+
+    let (Some(dutch_auction), Some(auction_start)) = (&stock.dutch_auction, stock.auction_start)
+    else {
+        return Err(StdError::generic_err(f!(
+            "Stock {stock_id} is not sold in a Dutch auction"
+        )));
+    };
+
+    let current_time = env.block.time.nanos() / 1_000_000;
+
+    // Nothing is left for sale once the auction has been settled
+    let remaining_shares = if stock.is_settled() {
+        0
+    } else {
+        opening_bid(deps.storage, &stock)?.map_or(0, |bid| bid.remaining_shares)
+    };
+
+    Ok(GetDutchAuctionResponse {
+        stock_id,
+        price_per_share: dutch_auction
+            .price_at(auction_start, current_time)
+            .to_string(),
+        remaining_shares,
+    })
+}
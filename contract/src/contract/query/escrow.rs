@@ -0,0 +1,41 @@
+use cosmwasm_std::{Deps, Env, StdResult};
+
+use crate::{
+    msg::{GetBuyOrderEscrowResponse, GetEscrowBalanceResponse},
+    state::{ACCRUED_FEES, BUY_ORDER_ESCROWS, CONFIG, TOTAL_ESCROW},
+};
+
+pub fn get_escrow_balance(deps: Deps, env: Env) -> StdResult<GetEscrowBalanceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let total_escrow = TOTAL_ESCROW.may_load(deps.storage)?.unwrap_or(0);
+    let accrued_fees = ACCRUED_FEES.may_load(deps.storage)?.unwrap_or(0);
+
+    let contract_balance = deps
+        .querier
+        .query_balance(env.contract.address, config.denom)?
+        .amount
+        .u128();
+
+    Ok(GetEscrowBalanceResponse {
+        total_escrow: total_escrow.to_string(),
+        contract_balance: contract_balance.to_string(),
+        accrued_fees: accrued_fees.to_string(),
+        // Accrued fees are held in the same balance as the escrow
+        is_solvent: contract_balance >= total_escrow + accrued_fees,
+    })
+}
+
+pub fn get_buy_order_escrow(
+    deps: Deps,
+    _env: Env,
+    buy_order_id: u64,
+) -> StdResult<GetBuyOrderEscrowResponse> {
+    let amount = BUY_ORDER_ESCROWS
+        .may_load(deps.storage, &buy_order_id.to_be_bytes())?
+        .unwrap_or(0);
+
+    Ok(GetBuyOrderEscrowResponse {
+        buy_order_id,
+        amount: amount.to_string(),
+    })
+}
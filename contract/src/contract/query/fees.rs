@@ -0,0 +1,17 @@
+use cosmwasm_std::{Deps, Env, StdResult};
+
+use crate::{
+    msg::GetAccruedFeesResponse,
+    state::{ACCRUED_FEES, CONFIG},
+};
+
+pub fn get_accrued_fees(deps: Deps, _env: Env) -> StdResult<GetAccruedFeesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = ACCRUED_FEES.may_load(deps.storage)?.unwrap_or(0);
+
+    Ok(GetAccruedFeesResponse {
+        amount: amount.to_string(),
+        fee_bps: config.fee_bps,
+        fee_collector: config.fee_collector,
+    })
+}
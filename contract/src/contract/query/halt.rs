@@ -0,0 +1,25 @@
+use cosmwasm_std::{Deps, Env, StdResult};
+
+use crate::{
+    msg::GetHaltStatusResponse,
+    state::{GLOBAL_HALT, STOCK_HALTS},
+};
+
+pub fn get_halt_status(
+    deps: Deps,
+    _env: Env,
+    stock_id: Option<u64>,
+) -> StdResult<GetHaltStatusResponse> {
+    let global = GLOBAL_HALT.may_load(deps.storage)?;
+
+    let stock = match stock_id {
+        Some(stock_id) => STOCK_HALTS.may_load(deps.storage, &stock_id.to_be_bytes())?,
+        None => None,
+    };
+
+    Ok(GetHaltStatusResponse {
+        is_halted: global.is_some() || stock.is_some(),
+        global,
+        stock,
+    })
+}
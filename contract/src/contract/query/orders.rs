@@ -1,3 +1,4 @@
+use crate::contract::order_book::{buy_orders_by_price, sell_orders_by_price};
 use crate::msg::{
     GetBuyOrderByIdResponse, GetBuyOrdersResponse, GetBuyPriceResponse, GetSellOrderByIdResponse,
     GetSellOrdersResponse, GetSellPriceResponse, GetTotalBuyVolumeResponse,
@@ -14,36 +15,28 @@ pub fn get_open_sell_orders_by_stock_id(
     stock_id: u64,
     sort_by: OrderSort,
 ) -> StdResult<GetSellOrdersResponse> {
-    // Get orders by stock_id
-    let mut orders = SELL_ORDERS
-        .idx
-        .stock_id
-        .prefix(stock_id)
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        // filter by open orders
-        .filter_map(|item| {
-            if let Ok((_, order)) = item {
-                if order.resolved_at.is_none() {
-                    Some(order)
-                } else {
-                    None
-                }
+    let orders = match sort_by {
+        // the open order book is already sorted by price
+        OrderSort::PriceAsc => {
+            sell_orders_by_price(deps.storage, stock_id, Order::Ascending).collect()
+        }
+        OrderSort::PriceDesc => {
+            sell_orders_by_price(deps.storage, stock_id, Order::Descending).collect()
+        }
+        // sort by date created
+        OrderSort::CreatedAtAsc | OrderSort::CreatedAtDesc => {
+            let mut orders = sell_orders_by_price(deps.storage, stock_id, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+
+            if sort_by == OrderSort::CreatedAtAsc {
+                orders.sort_by_key(|order| (order.created_at, order.id));
             } else {
-                None
+                orders.sort_by_key(|order| std::cmp::Reverse((order.created_at, order.id)));
             }
-        })
-        .collect::<Vec<_>>();
 
-    match sort_by {
-        // sort by date created (ascending)
-        OrderSort::CreatedAtAsc => orders.sort_by(|a, b| a.created_at.cmp(&b.created_at)),
-        // sort by date created (descending)
-        OrderSort::CreatedAtDesc => orders.sort_by(|a, b| b.created_at.cmp(&a.created_at)),
-        // sort by price created (ascending)
-        OrderSort::PriceAsc => orders.sort_by(|a, b| a.price_per_share.cmp(&b.price_per_share)),
-        // sort by price created (descending)
-        OrderSort::PriceDesc => orders.sort_by(|a, b| b.price_per_share.cmp(&a.price_per_share)),
-    }
+            Ok(orders)
+        }
+    }?;
 
     Ok(GetSellOrdersResponse { orders })
 }
@@ -54,36 +47,28 @@ pub fn get_open_buy_orders_by_stock_id(
     stock_id: u64,
     sort_by: OrderSort,
 ) -> StdResult<GetBuyOrdersResponse> {
-    // Get orders by stock_id
-    let mut orders = BUY_ORDERS
-        .idx
-        .stock_id
-        .prefix(stock_id)
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        // filter by open orders
-        .filter_map(|item| {
-            if let Ok((_, order)) = item {
-                if order.resolved_at.is_none() {
-                    Some(order)
-                } else {
-                    None
-                }
+    let orders = match sort_by {
+        // the open order book is already sorted by price (highest first)
+        OrderSort::PriceDesc => {
+            buy_orders_by_price(deps.storage, stock_id, Order::Ascending).collect()
+        }
+        OrderSort::PriceAsc => {
+            buy_orders_by_price(deps.storage, stock_id, Order::Descending).collect()
+        }
+        // sort by date created
+        OrderSort::CreatedAtAsc | OrderSort::CreatedAtDesc => {
+            let mut orders = buy_orders_by_price(deps.storage, stock_id, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+
+            if sort_by == OrderSort::CreatedAtAsc {
+                orders.sort_by_key(|order| (order.created_at, order.id));
             } else {
-                None
+                orders.sort_by_key(|order| std::cmp::Reverse((order.created_at, order.id)));
             }
-        })
-        .collect::<Vec<_>>();
 
-    match sort_by {
-        // sort by date created (ascending)
-        OrderSort::CreatedAtAsc => orders.sort_by(|a, b| a.created_at.cmp(&b.created_at)),
-        // sort by date created (descending)
-        OrderSort::CreatedAtDesc => orders.sort_by(|a, b| b.created_at.cmp(&a.created_at)),
-        // sort by price created (ascending)
-        OrderSort::PriceAsc => orders.sort_by(|a, b| a.price_per_share.cmp(&b.price_per_share)),
-        // sort by price created (descending)
-        OrderSort::PriceDesc => orders.sort_by(|a, b| b.price_per_share.cmp(&a.price_per_share)),
-    }
+            Ok(orders)
+        }
+    }?;
 
     Ok(GetBuyOrdersResponse { orders })
 }
@@ -194,15 +179,15 @@ pub fn get_open_buy_orders_by_owner(
 
 pub fn get_total_sell_order_volume(
     deps: Deps,
-    env: Env,
+    _env: Env,
     stock_id: u64,
 ) -> StdResult<GetTotalSellVolumeResponse> {
-    let open_sell_orders =
-        get_open_sell_orders_by_stock_id(deps, env, stock_id, OrderSort::PriceAsc)?.orders;
+    let mut total_available_shares = 0u64;
 
-    let total_available_shares = open_sell_orders.iter().fold(0u64, |acc, order| {
-        acc + (order.available_shares - order.sold_shares)
-    });
+    for order in sell_orders_by_price(deps.storage, stock_id, Order::Ascending) {
+        let order = order?;
+        total_available_shares += order.available_shares - order.sold_shares;
+    }
 
     Ok(GetTotalSellVolumeResponse {
         amount: total_available_shares,
@@ -211,15 +196,15 @@ pub fn get_total_sell_order_volume(
 
 pub fn get_total_buy_order_volume(
     deps: Deps,
-    env: Env,
+    _env: Env,
     stock_id: u64,
 ) -> StdResult<GetTotalBuyVolumeResponse> {
-    let open_buy_orders =
-        get_open_buy_orders_by_stock_id(deps, env, stock_id, OrderSort::PriceDesc)?.orders;
+    let mut total_available_shares = 0u64;
 
-    let total_available_shares = open_buy_orders.iter().fold(0u64, |acc, order| {
-        acc + (order.requested_shares - order.bought_shares)
-    });
+    for order in buy_orders_by_price(deps.storage, stock_id, Order::Ascending) {
+        let order = order?;
+        total_available_shares += order.requested_shares - order.bought_shares;
+    }
 
     Ok(GetTotalBuyVolumeResponse {
         amount: total_available_shares,
@@ -228,37 +213,37 @@ pub fn get_total_buy_order_volume(
 
 pub fn get_buy_price(
     deps: Deps,
-    env: Env,
+    _env: Env,
     stock_id: u64,
     requested_shares: u64,
 ) -> StdResult<GetBuyPriceResponse> {
-    let open_sell_orders =
-        get_open_sell_orders_by_stock_id(deps, env.clone(), stock_id, OrderSort::PriceAsc)?.orders;
-
-    // Check if there are enough shares available for sale
-    let available_volume = get_total_sell_order_volume(deps, env.clone(), stock_id)?.amount;
-
-    if available_volume < requested_shares {
-        return Err(StdError::generic_err(f!(
-            "Not enough sell orders to fulfill buy request for {requested_shares} shares. Only {available_volume} shares available."
-        )));
-    }
-
     let mut remaining_shares = requested_shares;
+    let mut available_volume = 0u64;
     let mut total_price = 0u128;
 
-    // To buy shares, we need to match with sell orders, starting from the lowest price
-    for order in open_sell_orders {
+    // To buy shares, we need to match with sell orders, starting from the lowest price,
+    // only walking as far into the book as the request needs
+    for order in sell_orders_by_price(deps.storage, stock_id, Order::Ascending) {
         if remaining_shares == 0 {
             break;
         }
 
+        let order = order?;
+
         let shares_from_this_order =
             std::cmp::min(remaining_shares, order.available_shares - order.sold_shares);
-        let price_from_this_order = shares_from_this_order as u128 * order.price_per_share as u128;
+        let price_from_this_order = shares_from_this_order as u128 * order.price_per_share;
 
         total_price += price_from_this_order;
         remaining_shares -= shares_from_this_order;
+        available_volume += shares_from_this_order;
+    }
+
+    // Check if there are enough shares available for sale
+    if remaining_shares > 0 {
+        return Err(StdError::generic_err(f!(
+            "Not enough sell orders to fulfill buy request for {requested_shares} shares. Only {available_volume} shares available."
+        )));
     }
 
     let price_per_share = if requested_shares > 0 {
@@ -276,39 +261,39 @@ pub fn get_buy_price(
 
 pub fn get_sell_price(
     deps: Deps,
-    env: Env,
+    _env: Env,
     stock_id: u64,
     requested_shares: u64,
 ) -> StdResult<GetSellPriceResponse> {
-    let open_buy_orders =
-        get_open_buy_orders_by_stock_id(deps, env.clone(), stock_id, OrderSort::PriceDesc)?.orders;
-
-    // Check if there are enough shares available to buy
-    let available_volume = get_total_buy_order_volume(deps, env.clone(), stock_id)?.amount;
-
-    if available_volume < requested_shares {
-        return Err(StdError::generic_err(f!(
-            "Not enough buy orders to fulfill sell request for {requested_shares} shares. Only {available_volume} shares in demand."
-        )));
-    }
-
     let mut remaining_shares = requested_shares;
+    let mut available_volume = 0u64;
     let mut total_price = 0u128;
 
-    // To sell shares, we need to match with buy orders, starting from the highest price
-    for order in open_buy_orders {
+    // To sell shares, we need to match with buy orders, starting from the highest price,
+    // only walking as far into the book as the request needs
+    for order in buy_orders_by_price(deps.storage, stock_id, Order::Ascending) {
         if remaining_shares == 0 {
             break;
         }
 
+        let order = order?;
+
         let shares_from_this_order = std::cmp::min(
             remaining_shares,
             order.requested_shares - order.bought_shares,
         );
-        let price_from_this_order = shares_from_this_order as u128 * order.price_per_share as u128;
+        let price_from_this_order = shares_from_this_order as u128 * order.price_per_share;
 
         total_price += price_from_this_order;
         remaining_shares -= shares_from_this_order;
+        available_volume += shares_from_this_order;
+    }
+
+    // Check if there are enough shares available to buy
+    if remaining_shares > 0 {
+        return Err(StdError::generic_err(f!(
+            "Not enough buy orders to fulfill sell request for {requested_shares} shares. Only {available_volume} shares in demand."
+        )));
     }
 
     let price_per_share = if requested_shares > 0 {
@@ -0,0 +1,24 @@
+use cosmwasm_std::{Deps, Env, Order, StdResult};
+
+use crate::{
+    msg::{GetOwnershipResponse, GetRoleMembersResponse},
+    state::{Role, ROLES, STATE},
+};
+
+pub fn get_ownership(deps: Deps, _env: Env) -> StdResult<GetOwnershipResponse> {
+    let state = STATE.load(deps.storage)?;
+
+    Ok(GetOwnershipResponse {
+        owner: state.owner,
+        pending_owner: state.pending_owner,
+    })
+}
+
+pub fn get_role_members(deps: Deps, _env: Env, role: Role) -> StdResult<GetRoleMembersResponse> {
+    let members = ROLES
+        .prefix(role.as_str())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GetRoleMembersResponse { role, members })
+}
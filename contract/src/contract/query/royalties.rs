@@ -0,0 +1,25 @@
+use cosmwasm_std::{Deps, Env, StdError, StdResult};
+
+use crate::{
+    msg::GetRoyaltyResponse,
+    state::{ROYALTIES, STOCKS},
+};
+
+use format as f;
+
+pub fn get_royalty(deps: Deps, _env: Env, stock_id: u64) -> StdResult<GetRoyaltyResponse> {
+    let stock = STOCKS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| StdError::not_found(f!("Stock with id {stock_id}")))?;
+
+    let royalty = ROYALTIES
+        .may_load(deps.storage, &stock_id.to_be_bytes())?
+        .unwrap_or_default();
+
+    Ok(GetRoyaltyResponse {
+        stock_id,
+        royalty_bps: royalty.royalty_bps,
+        payout_address: royalty.payout_address.unwrap_or(stock.influencer),
+        total_paid: royalty.total_paid.to_string(),
+    })
+}
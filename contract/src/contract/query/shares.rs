@@ -1,7 +1,7 @@
 use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult};
 
 use crate::{
-    msg::{GetShareByIdResponse, GetSharesResponse},
+    msg::{GetShareResponse, GetSharesResponse},
     state::SHARES,
 };
 
@@ -15,38 +15,36 @@ pub fn get_shares_by_stock_id(
     // Query Shares by stock_id in descending order based on the no_of_shares,
 
     let mut shares = SHARES
-        // filter to stocks that belongs to the specified influencer
-        .idx
-        .stock_id
+        // positions are keyed by (stock_id, owner)
         .prefix(stock_id)
         .range(deps.storage, None, None, Order::Ascending)
         // Extract the share data from each item.
-        .map(|item| item.and_then(|(_, share)| Ok(share)))
+        .map(|item| item.map(|(_, share)| share))
         .collect::<Result<Vec<_>, _>>()?;
 
-    shares.sort_by(|a, b| b.no_of_shares.cmp(&a.no_of_shares));
+    shares.sort_by_key(|share| std::cmp::Reverse(share.no_of_shares));
 
     Ok(GetSharesResponse { shares })
 }
 
 pub fn get_shares_by_owner(deps: Deps, _env: Env, owner: Addr) -> StdResult<GetSharesResponse> {
     let shares = SHARES
-        // filter to stocks that belongs to the specified influencer
+        // filter to positions that belong to the specified owner
         .idx
         .owner
         .prefix(owner)
         .range(deps.storage, None, None, Order::Descending)
         // Extract the share data from each item.
-        .map(|item| item.and_then(|(_, share)| Ok(share)))
+        .map(|item| item.map(|(_, share)| share))
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(GetSharesResponse { shares })
 }
 
-pub fn get_shares_by_id(deps: Deps, _env: Env, share_id: u64) -> StdResult<GetShareByIdResponse> {
+pub fn get_share(deps: Deps, _env: Env, stock_id: u64, owner: Addr) -> StdResult<GetShareResponse> {
     let share = SHARES
-        .load(deps.storage, &share_id.to_be_bytes())
-        .map_err(|_| StdError::not_found(f!("Shares with id {share_id}")))?;
+        .load(deps.storage, (stock_id, &owner))
+        .map_err(|_| StdError::not_found(f!("Shares of stock {stock_id} owned by {owner}")))?;
 
-    Ok(GetShareByIdResponse { share })
+    Ok(GetShareResponse { share })
 }
@@ -23,6 +23,7 @@ pub fn get_all_stocks(
     in_auction: Option<bool>,
     in_sale: Option<bool>,
     marked_as_active_auction: Option<bool>,
+    scheduled: Option<bool>,
 ) -> StdResult<GetStocksResponse> {
     // Set starting point for the query
     // (where the next stock_id should start from, excluding the `start_after` id)
@@ -47,7 +48,10 @@ pub fn get_all_stocks(
                 .into_iter()
                 .filter(|stock| {
                     // If auction has started but has not ended
-                    stock.auction_start.is_some() && stock.auction_end > Some(current_time)
+                    stock
+                        .auction_start
+                        .is_some_and(|start| start <= current_time)
+                        && stock.auction_end > Some(current_time)
                 })
                 .collect();
         } else {
@@ -55,31 +59,18 @@ pub fn get_all_stocks(
                 .into_iter()
                 .filter(|stock| {
                     // If auction has not started or auction has ended
-                    !(stock.auction_start.is_some() && stock.auction_end > Some(current_time))
+                    !(stock
+                        .auction_start
+                        .is_some_and(|start| start <= current_time)
+                        && stock.auction_end > Some(current_time))
                 })
                 .collect();
         }
     }
 
-    // Filter by sales status
+    // Filter by sales status, a stock trades once its auction is settled until it is delisted
     if let Some(in_sale) = in_sale {
-        if in_sale {
-            stocks = stocks
-                .into_iter()
-                // If auction has ended
-                .filter(|stock| {
-                    stock.auction_end.is_some() && stock.auction_end <= Some(current_time)
-                })
-                .collect();
-        } else {
-            stocks = stocks
-                .into_iter()
-                // If auction not ended
-                .filter(|stock| {
-                    !(stock.auction_end.is_some() && stock.auction_end <= Some(current_time))
-                })
-                .collect();
-        }
+        stocks.retain(|stock| (stock.is_settled() && stock.delisted_at.is_none()) == in_sale);
     }
 
     // Filter by marked_as_active_auction
@@ -91,6 +82,17 @@ pub fn get_all_stocks(
             .collect();
     }
 
+    // Filter by scheduled auctions
+    if let Some(scheduled) = scheduled {
+        // If auction is set to start later
+        stocks.retain(|stock| {
+            stock
+                .auction_start
+                .is_some_and(|start| start > current_time)
+                == scheduled
+        });
+    }
+
     Ok(GetStocksResponse { stocks })
 }
 
@@ -0,0 +1,39 @@
+use cosmwasm_std::{Deps, Env, Order, StdError, StdResult};
+
+use crate::{
+    contract::tickers::normalize_ticker,
+    msg::{GetReservedTickersResponse, GetStockByTickerResponse, ReservedTicker},
+    state::{RESERVED_TICKERS, STOCKS, TICKERS},
+};
+
+use format as f;
+
+pub fn get_stock_by_ticker(
+    deps: Deps,
+    _env: Env,
+    ticker: String,
+) -> StdResult<GetStockByTickerResponse> {
+    let ticker = normalize_ticker(&ticker);
+
+    let stock_id = TICKERS
+        .load(deps.storage, &ticker)
+        .map_err(|_| StdError::not_found(f!("Stock with ticker {ticker}")))?;
+
+    let stock = STOCKS.load(deps.storage, &stock_id.to_be_bytes())?;
+
+    Ok(GetStockByTickerResponse { stock })
+}
+
+pub fn get_reserved_tickers(deps: Deps, _env: Env) -> StdResult<GetReservedTickersResponse> {
+    let tickers = RESERVED_TICKERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(ticker, reservation)| ReservedTicker {
+                ticker,
+                holder: reservation.holder,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GetReservedTickersResponse { tickers })
+}
@@ -0,0 +1,21 @@
+use cosmwasm_std::{Deps, Env, StdError, StdResult};
+
+use crate::{msg::GetVestingResponse, state::VESTINGS};
+
+use format as f;
+
+pub fn get_vesting(deps: Deps, env: Env, stock_id: u64) -> StdResult<GetVestingResponse> {
+    let vesting = VESTINGS
+        .load(deps.storage, &stock_id.to_be_bytes())
+        .map_err(|_| StdError::not_found(f!("Vesting of stock {stock_id}")))?;
+
+    let current_time = env.block.time.nanos() / 1_000_000;
+    let vested = vesting.vested_at(current_time);
+
+    Ok(GetVestingResponse {
+        vested,
+        claimable: vested - vesting.claimed_shares,
+        locked: vesting.total_shares - vested,
+        vesting,
+    })
+}
@@ -0,0 +1,37 @@
+use crate::ContractError;
+
+use format as f;
+
+pub const TICKER_MIN_LENGTH: usize = 2;
+pub const TICKER_MAX_LENGTH: usize = 12;
+
+// Tickers are compared and stored uppercase, surrounding whitespace ignored
+pub fn normalize_ticker(ticker: &str) -> String {
+    ticker.trim().to_uppercase()
+}
+
+// A normalized ticker is 2 to 12 letters, digits or underscores, starting with a letter
+pub fn validate_ticker(ticker: &str) -> Result<(), ContractError> {
+    if ticker.len() < TICKER_MIN_LENGTH || ticker.len() > TICKER_MAX_LENGTH {
+        return Err(ContractError::GenericError(f!(
+            "Ticker must be between {TICKER_MIN_LENGTH} and {TICKER_MAX_LENGTH} characters"
+        )));
+    }
+
+    if !ticker.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return Err(ContractError::GenericError(
+            "Ticker must start with a letter".to_string(),
+        ));
+    }
+
+    if !ticker
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(ContractError::GenericError(
+            "Ticker can only contain letters, digits and underscores".to_string(),
+        ));
+    }
+
+    Ok(())
+}
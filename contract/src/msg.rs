@@ -1,31 +1,171 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::Addr;
 
-use crate::state::{Bid, BuyOrder, Sale, SellOrder, Share, Stock};
+use crate::state::{
+    Bid, BuyOrder, Config, DutchAuction, HaltInfo, Presale, RetainedAllocation, Role, Sale,
+    SealedBid, SealedBidAuction, SellOrder, Share, Stock, StockMetadata, Vesting,
+};
 
+// Every field falls back to its default when left out
 #[cw_serde]
-pub struct InstantiateMsg {}
+#[derive(Default)]
+pub struct InstantiateMsg {
+    pub denom: Option<String>,
+    pub total_shares: Option<u64>,
+    // Both default to total_shares, so stocks can only be created with the default supply
+    pub min_total_shares: Option<u64>,
+    pub max_total_shares: Option<u64>,
+    // in milliseconds
+    pub auction_duration: Option<u64>,
+    // Both default to auction_duration
+    pub min_auction_duration: Option<u64>,
+    pub max_auction_duration: Option<u64>,
+    pub min_bid_increment: Option<u128>,
+    // Soft close settings in milliseconds, off by default
+    pub soft_close_window: Option<u64>,
+    pub soft_close_extension: Option<u64>,
+    pub max_auction_extension: Option<u64>,
+    // in milliseconds, bids can be cancelled until the end by default
+    pub bid_lock_window: Option<u64>,
+    // Protocol fee in basis points, no fee by default
+    pub fee_bps: Option<u16>,
+    pub fee_collector: Option<String>,
+    // Reward for settling an auction in basis points, none by default
+    pub keeper_reward_bps: Option<u16>,
+    // Highest royalty an influencer may set, no royalties by default
+    pub max_royalty_bps: Option<u16>,
+    // Share of an unrevealed sealed bid's deposit that is kept, none by default
+    pub unrevealed_slash_bps: Option<u16>,
+}
+
+// Fields left out keep their current value
+#[cw_serde]
+#[derive(Default)]
+pub struct UpdateConfigMsg {
+    pub denom: Option<String>,
+    pub total_shares: Option<u64>,
+    pub min_total_shares: Option<u64>,
+    pub max_total_shares: Option<u64>,
+    // in milliseconds
+    pub auction_duration: Option<u64>,
+    pub min_auction_duration: Option<u64>,
+    pub max_auction_duration: Option<u64>,
+    pub min_bid_increment: Option<u128>,
+    pub soft_close_window: Option<u64>,
+    pub soft_close_extension: Option<u64>,
+    pub max_auction_extension: Option<u64>,
+    pub bid_lock_window: Option<u64>,
+    pub keeper_reward_bps: Option<u16>,
+    pub max_royalty_bps: Option<u16>,
+    pub unrevealed_slash_bps: Option<u16>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
 
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateStock {
         ticker: String,
+        // Defaults to the configured total_shares
+        total_shares: Option<u64>,
+        metadata: Option<StockMetadata>,
     },
 
     StartAuction {
         stock_id: u64,
+        // Timestamp in milliseconds, the auction starts right away when not set
+        start_at: Option<u64>,
+        // in milliseconds, defaults to the configured auction_duration
+        duration: Option<u64>,
+        // Lowest price per share a bid may offer, none by default
+        reserve_price: Option<u128>,
+        // Every winner pays the lowest winning price, off by default
+        uniform_price: Option<bool>,
+        // Sells the shares at a price that drops over time instead of taking bids
+        dutch_auction: Option<DutchAuction>,
+        // Takes sealed bids that are revealed once the auction ends
+        sealed_bid: Option<SealedBidAuction>,
+        // Every bid is refunded if the auction raises less than this
+        funding_goal: Option<u128>,
+        // Caps the shares one address can hold across its open bids
+        max_shares_per_address: Option<u64>,
+        // Fewest shares a single bid can be for
+        min_shares_per_bid: Option<u64>,
+        // Opens the auction with a presale for allow-listed addresses
+        presale: Option<Presale>,
+        // Keeps part of the supply for the influencer, vested after settlement
+        retained_allocation: Option<RetainedAllocation>,
     },
 
+    // Closes the auction and settles its first batch of bids. Anyone can once
+    // it is over, the influencer and operators can also end it early.
     EndAuction {
         stock_id: u64,
     },
 
+    // Settles the next batch of up to `limit` bids, closing the auction first if needed.
+    // Has to be called until the response reports the settlement complete.
+    SettleAuction {
+        stock_id: u64,
+        limit: Option<u32>,
+    },
+
+    // Moves the influencer's retained shares that have vested into their position
+    ClaimVested {
+        stock_id: u64,
+    },
+
     PlaceBid {
         stock_id: u64,
         price_per_share: u128,
         shares: u64,
     },
 
+    // Places a bid during a stock's presale. `proof` is the hex encoded Merkle proof
+    // that the sender is allow-listed for `allocation` shares.
+    PlacePresaleBid {
+        stock_id: u64,
+        price_per_share: u128,
+        shares: u64,
+        allocation: u64,
+        proof: Vec<String>,
+    },
+
+    // Commits to a sealed bid, the funds sent are its deposit.
+    // `commitment` is the hex encoded sha256 of "{bidder}:{price_per_share}:{shares}:{salt}".
+    CommitBid {
+        stock_id: u64,
+        commitment: String,
+    },
+
+    // Opens a sealed bid once the auction's commit phase is over
+    RevealBid {
+        bid_id: u64,
+        price_per_share: u128,
+        shares: u64,
+        salt: String,
+    },
+
+    // Withdraws an open bid and refunds its remaining shares.
+    // Not allowed within the configured bid_lock_window of the auction's end.
+    CancelBid {
+        bid_id: u64,
+    },
+
+    // Raises an open bid's price and/or adds shares to it, charging only the difference
+    IncreaseBid {
+        bid_id: u64,
+        price_per_share: Option<u128>,
+        additional_shares: Option<u64>,
+    },
+
+    // Buys shares in a Dutch auction at its current price
+    BuyAuctionShares {
+        stock_id: u64,
+        shares: u64,
+    },
+
     CreateBuyOrder {
         stock_id: u64,
         price_per_share: u128,
@@ -60,6 +200,85 @@ pub enum ExecuteMsg {
         // 1 = 1% slippage
         slippage: u64,
     },
+
+    // Owner only
+    UpdateConfig(UpdateConfigMsg),
+
+    // Owner only, the proposed owner has to accept before the transfer happens
+    ProposeNewOwner {
+        new_owner: String,
+    },
+
+    // Called by the proposed owner
+    AcceptOwnership {},
+
+    // Owner only, leaves the contract without an owner
+    RenounceOwnership {},
+
+    // Owner only
+    GrantRole {
+        role: Role,
+        address: String,
+    },
+
+    // Owner only
+    RevokeRole {
+        role: Role,
+        address: String,
+    },
+
+    // Fee manager only, fields left out keep their current value
+    UpdateFeeConfig {
+        fee_bps: Option<u16>,
+        fee_collector: Option<String>,
+    },
+
+    // Owner only, sends the accrued fees to the fee collector
+    WithdrawFees {},
+
+    // Influencer only, royalty taken from every secondary sale of the stock.
+    // Paid to payout_address if set, to the influencer otherwise.
+    SetRoyalty {
+        stock_id: u64,
+        royalty_bps: u16,
+        payout_address: Option<String>,
+    },
+
+    // Operator only, halts all trading or a single stock's.
+    // Bids and new orders are rejected while halted, cancels are not.
+    Halt {
+        stock_id: Option<u64>,
+        reason: String,
+    },
+
+    // Operator only, lifts a halt set by Halt
+    Resume {
+        stock_id: Option<u64>,
+    },
+
+    // Operator only, stops a stock from trading for good.
+    // Its ticker stays taken until the owner releases or reserves it.
+    DelistStock {
+        stock_id: u64,
+    },
+
+    // Owner only, keeps a ticker from being used, except by holder if set.
+    // Takes the ticker from the stock using it if that stock is delisted.
+    ReserveTicker {
+        ticker: String,
+        holder: Option<String>,
+    },
+
+    // Owner only, makes a reserved ticker, or one used by a delisted stock, available again
+    ReleaseTicker {
+        ticker: String,
+    },
+
+    // Influencer only, replaces the stock's metadata
+    UpdateStockMetadata {
+        stock_id: u64,
+        metadata: StockMetadata,
+    },
 }
 
 #[cw_serde]
@@ -75,6 +294,8 @@ pub enum QueryMsg {
         in_auction: Option<bool>,
         in_sale: Option<bool>,
         marked_as_active_auction: Option<bool>,
+        // Auctions that have been scheduled but haven't started yet
+        scheduled: Option<bool>,
     },
 
     #[returns(GetStocksResponse)]
@@ -104,6 +325,8 @@ pub enum QueryMsg {
     GetMinimumBidPrice {
         stock_id: u64,
         shares_requested: u64,
+        // Reports how many more shares this address may bid for
+        bidder: Option<Addr>,
     },
 
     #[returns(GetSharesResponse)]
@@ -112,8 +335,8 @@ pub enum QueryMsg {
     #[returns(GetSharesResponse)]
     GetSharesByOwner { owner: Addr },
 
-    #[returns(GetShareByIdResponse)]
-    GetShareById { share_id: u64 },
+    #[returns(GetShareResponse)]
+    GetShare { stock_id: u64, owner: Addr },
 
     #[returns(GetSellPriceResponse)]
     GetSellPrice {
@@ -159,6 +382,45 @@ pub enum QueryMsg {
 
     #[returns(GetSalesByUserResponse)]
     GetSalesByUser { user: Addr },
+
+    #[returns(GetEscrowBalanceResponse)]
+    GetEscrowBalance {},
+
+    #[returns(GetBuyOrderEscrowResponse)]
+    GetBuyOrderEscrow { buy_order_id: u64 },
+
+    #[returns(GetConfigResponse)]
+    GetConfig {},
+
+    #[returns(GetOwnershipResponse)]
+    GetOwnership {},
+
+    #[returns(GetRoleMembersResponse)]
+    GetRoleMembers { role: Role },
+
+    #[returns(GetHaltStatusResponse)]
+    GetHaltStatus { stock_id: Option<u64> },
+
+    #[returns(GetAccruedFeesResponse)]
+    GetAccruedFees {},
+
+    #[returns(GetRoyaltyResponse)]
+    GetRoyalty { stock_id: u64 },
+
+    #[returns(GetStockByTickerResponse)]
+    GetStockByTicker { ticker: String },
+
+    #[returns(GetReservedTickersResponse)]
+    GetReservedTickers {},
+
+    #[returns(GetDutchAuctionResponse)]
+    GetDutchAuction { stock_id: u64 },
+
+    #[returns(GetSealedBidResponse)]
+    GetSealedBid { bid_id: u64 },
+
+    #[returns(GetVestingResponse)]
+    GetVesting { stock_id: u64 },
 }
 
 #[cw_serde]
@@ -169,11 +431,94 @@ pub enum OrderSort {
     CreatedAtDesc,
 }
 
+#[cw_serde]
+pub struct GetConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct GetOwnershipResponse {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct GetRoleMembersResponse {
+    pub role: Role,
+    pub members: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct GetHaltStatusResponse {
+    // Whether bids and new orders are currently rejected (for the stock, if given)
+    pub is_halted: bool,
+    pub global: Option<HaltInfo>,
+    pub stock: Option<HaltInfo>,
+}
+
+#[cw_serde]
+pub struct GetAccruedFeesResponse {
+    pub amount: String,
+    pub fee_bps: u16,
+    pub fee_collector: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct GetRoyaltyResponse {
+    pub stock_id: u64,
+    pub royalty_bps: u16,
+    // Where royalties are paid
+    pub payout_address: Addr,
+    // Royalties paid since the stock was created
+    pub total_paid: String,
+}
+
 #[cw_serde]
 pub struct GetStockByIdResponse {
     pub stock: Stock,
 }
 
+#[cw_serde]
+pub struct GetStockByTickerResponse {
+    pub stock: Stock,
+}
+
+#[cw_serde]
+pub struct ReservedTicker {
+    pub ticker: String,
+    pub holder: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct GetReservedTickersResponse {
+    pub tickers: Vec<ReservedTicker>,
+}
+
+#[cw_serde]
+pub struct GetDutchAuctionResponse {
+    pub stock_id: u64,
+    // Price per share at the current block time
+    pub price_per_share: String,
+    // Shares still for sale
+    pub remaining_shares: u64,
+}
+
+#[cw_serde]
+pub struct GetSealedBidResponse {
+    pub sealed_bid: SealedBid,
+}
+
+#[cw_serde]
+pub struct GetVestingResponse {
+    pub vesting: Vesting,
+    // Released so far, claimed or not
+    pub vested: u64,
+    // Released but not yet claimed
+    pub claimable: u64,
+    // Not yet released
+    pub locked: u64,
+}
+
 #[cw_serde]
 pub struct GetStocksResponse {
     pub stocks: Vec<Stock>,
@@ -193,10 +538,12 @@ pub struct GetBidByIdResponse {
 pub struct GetMinimumBidPriceResponse {
     pub min_price: String,
     pub shares_requested: u64,
+    // Shares the bidder may still bid for, none when the stock has no cap
+    pub remaining_allocation: Option<u64>,
 }
 
 #[cw_serde]
-pub struct GetShareByIdResponse {
+pub struct GetShareResponse {
     pub share: Share,
 }
 
@@ -264,3 +611,18 @@ pub struct GetTotalBuyVolumeResponse {
 pub struct GetTotalSellVolumeResponse {
     pub amount: u64,
 }
+
+#[cw_serde]
+pub struct GetEscrowBalanceResponse {
+    pub total_escrow: String,
+    pub contract_balance: String,
+    pub accrued_fees: String,
+    // Whether the contract's balance covers the escrow and the accrued fees
+    pub is_solvent: bool,
+}
+
+#[cw_serde]
+pub struct GetBuyOrderEscrowResponse {
+    pub buy_order_id: u64,
+    pub amount: String,
+}
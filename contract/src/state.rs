@@ -1,26 +1,342 @@
+use cosmwasm_schema::cw_serde;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::Addr;
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct State {
-    pub owner: Addr,
+    // None once ownership has been renounced
+    pub owner: Option<Addr>,
+    // Proposed owner, waiting to accept ownership
+    #[serde(default)]
+    pub pending_owner: Option<Addr>,
 }
 
 pub const STATE: Item<State> = Item::new("state");
 
+// Privileged roles besides the owner, who implicitly holds every role
+#[cw_serde]
+#[derive(Copy)]
+pub enum Role {
+    // Ends auctions, halts and resumes trading
+    Operator,
+    // Sets the protocol fee and collector
+    FeeManager,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Operator => "operator",
+            Role::FeeManager => "fee_manager",
+        }
+    }
+}
+
+// Role holders keyed by (role, address)
+pub const ROLES: Map<(&str, &Addr), ()> = Map::new("roles");
+
+// Why and when trading was halted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct HaltInfo {
+    pub reason: String,
+    pub halted_at: u64,
+    pub halted_by: Addr,
+}
+
+// Set while all trading is halted
+pub const GLOBAL_HALT: Item<HaltInfo> = Item::new("global_halt");
+
+// Halted stocks keyed by stock_id
+pub const STOCK_HALTS: Map<&[u8], HaltInfo> = Map::new("stock_halt");
+
+// Market parameters, set at instantiate and changeable by the owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    // Denomination of the token stocks are traded in
+    pub denom: String,
+    // Number of shares a new stock is created with when the influencer doesn't pick one
+    pub total_shares: u64,
+    // Bounds on the number of shares an influencer may create a stock with
+    pub min_total_shares: u64,
+    pub max_total_shares: u64,
+    // Length of an auction in milliseconds when the influencer doesn't pick one
+    pub auction_duration: u64,
+    // Bounds on the auction length an influencer may pick, in milliseconds
+    pub min_auction_duration: u64,
+    pub max_auction_duration: u64,
+    // Amount a bid has to beat the price it outbids by
+    pub min_bid_increment: u128,
+    // A bid placed this close to the end of an auction extends it, in milliseconds.
+    // 0 turns the soft close off.
+    #[serde(default)]
+    pub soft_close_window: u64,
+    // How far each such bid pushes the end out, in milliseconds
+    #[serde(default)]
+    pub soft_close_extension: u64,
+    // Most an auction can be extended by in total, in milliseconds
+    #[serde(default)]
+    pub max_auction_extension: u64,
+    // Bids can't be cancelled this close to the end of an auction, in milliseconds
+    #[serde(default)]
+    pub bid_lock_window: u64,
+    // Protocol fee taken from every sale, in basis points
+    #[serde(default)]
+    pub fee_bps: u16,
+    // Where withdrawn fees are sent
+    #[serde(default)]
+    pub fee_collector: Option<Addr>,
+    // Share of an auction's proceeds paid to whoever settles it, in basis points.
+    // Held back from the influencer's pay, they get it back if they settle themselves.
+    #[serde(default)]
+    pub keeper_reward_bps: u16,
+    // Highest royalty an influencer may set on their stock, in basis points
+    #[serde(default)]
+    pub max_royalty_bps: u16,
+    // Part of a sealed bid's deposit that is kept when the bid isn't revealed, in basis points.
+    // It goes to the influencer, the rest is refunded.
+    #[serde(default)]
+    pub unrevealed_slash_bps: u16,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Stock {
     pub id: u64,
     pub ticker: String,
     pub influencer: Addr,
     pub total_shares: u64,
+    // May be in the future for a scheduled auction
     pub auction_start: Option<u64>,
     pub auction_end: Option<u64>,
+    // Latest the soft close can push auction_end to
+    #[serde(default)]
+    pub max_auction_end: Option<u64>,
+    // Lowest price per share a bid may offer
+    #[serde(default)]
+    pub reserve_price: u128,
+    // Every winner pays the same clearing price instead of their own bid
+    #[serde(default)]
+    pub uniform_price: bool,
+    // Price every winner of a uniform price auction pays, set when settlement starts
+    #[serde(default)]
+    pub clearing_price: Option<u128>,
+    // Set when the shares are sold in a descending price auction instead
+    #[serde(default)]
+    pub dutch_auction: Option<DutchAuction>,
+    // Set when bids are committed sealed and revealed after auction_end
+    #[serde(default)]
+    pub sealed_bid: Option<SealedBidAuction>,
+    // The auction only goes through if it raises at least this much,
+    // otherwise every bidder is refunded
+    #[serde(default)]
+    pub funding_goal: Option<u128>,
+    // What the winning bids of the current auction pay at their own price,
+    // kept up to date as bids are placed, raised, cancelled and outbid
+    #[serde(default)]
+    pub amount_raised: u128,
+    // The influencer's opening bid of the current auction
+    #[serde(default)]
+    pub opening_bid_id: Option<u64>,
+    // Most shares one address can hold across its open bids
+    #[serde(default)]
+    pub max_shares_per_address: Option<u64>,
+    // Fewest shares a single bid can be for
+    #[serde(default)]
+    pub min_shares_per_bid: Option<u64>,
+    // Set when the auction opens with an allow-listed presale
+    #[serde(default)]
+    pub presale: Option<Presale>,
+    // Part of the supply the influencer keeps out of the auction, released over time
+    #[serde(default)]
+    pub retained_allocation: Option<RetainedAllocation>,
     pub marked_as_active_auction: bool,
+    // Set from the moment the auction is closed until every bid has been settled
+    #[serde(default)]
+    pub settling: bool,
     pub created_at: u64,
+    // Set once the stock has been delisted, it can't be traded after that
+    #[serde(default)]
+    pub delisted_at: Option<u64>,
+    #[serde(default)]
+    pub metadata: StockMetadata,
+}
+
+impl Stock {
+    // Back to the state of a stock that has never been auctioned
+    pub fn reset_auction(&mut self) {
+        self.auction_start = None;
+        self.auction_end = None;
+        self.max_auction_end = None;
+        self.reserve_price = 0;
+        self.uniform_price = false;
+        self.clearing_price = None;
+        self.dutch_auction = None;
+        self.sealed_bid = None;
+        self.funding_goal = None;
+        self.amount_raised = 0;
+        self.opening_bid_id = None;
+        self.max_shares_per_address = None;
+        self.min_shares_per_bid = None;
+        self.presale = None;
+        self.retained_allocation = None;
+        self.marked_as_active_auction = false;
+        self.settling = false;
+    }
+
+    // Shares the influencer keeps under vesting
+    pub fn retained_shares(&self) -> u64 {
+        self.retained_allocation
+            .as_ref()
+            .map_or(0, |retained| retained.shares_of(self.total_shares))
+    }
+
+    // Shares that go up for auction, the supply less what the influencer retains
+    pub fn auctioned_shares(&self) -> u64 {
+        self.total_shares - self.retained_shares()
+    }
+
+    // End of the reveal phase of a sealed bid auction
+    pub fn reveal_end(&self) -> Option<u64> {
+        let sealed_bid = self.sealed_bid.as_ref()?;
+
+        self.auction_end
+            .map(|auction_end| auction_end + sealed_bid.reveal_duration)
+    }
+
+    // Whether the auction has been settled and the stock can trade on the order book
+    pub fn is_settled(&self) -> bool {
+        self.auction_start.is_some() && !self.marked_as_active_auction
+    }
+}
+
+// Price schedule of a descending price auction. The price starts at `start_price`
+// and drops by `price_decrement` every `decrement_interval` milliseconds until it
+// reaches `floor_price`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DutchAuction {
+    pub start_price: u128,
+    pub floor_price: u128,
+    pub price_decrement: u128,
+    pub decrement_interval: u64,
+}
+
+impl DutchAuction {
+    // Price per share at `time` for an auction that started at `auction_start`
+    pub fn price_at(&self, auction_start: u64, time: u64) -> u128 {
+        let steps = time.saturating_sub(auction_start) / self.decrement_interval;
+        let decrease = self.price_decrement.saturating_mul(steps as u128);
+
+        self.start_price
+            .saturating_sub(decrease)
+            .max(self.floor_price)
+    }
+}
+
+// Bids are committed until auction_end and can be revealed for
+// `reveal_duration` milliseconds after that
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SealedBidAuction {
+    pub reveal_duration: u64,
+}
+
+// Until `public_start` only addresses on the allow list can bid, each up to its allocation.
+// `merkle_root` is the hex encoded root of a Merkle tree whose leaves are the
+// sha256 of "{address}:{allocation}".
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Presale {
+    pub merkle_root: String,
+    pub public_start: u64,
+}
+
+// `retained_bps` of the supply is kept by the influencer. It vests linearly over
+// `vesting_duration` milliseconds from the auction's settlement, none of it before `cliff`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RetainedAllocation {
+    pub retained_bps: u16,
+    pub cliff: u64,
+    pub vesting_duration: u64,
+}
+
+impl RetainedAllocation {
+    // Shares retained out of a supply of `total_shares`
+    pub fn shares_of(&self, total_shares: u64) -> u64 {
+        (total_shares as u128 * self.retained_bps as u128 / 10_000) as u64
+    }
+}
+
+// Retained shares of a stock, held by the contract until they are claimed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Vesting {
+    pub stock_id: u64,
+    pub beneficiary: Addr,
+    pub total_shares: u64,
+    pub claimed_shares: u64,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+impl Vesting {
+    // Shares released by `time`
+    pub fn vested_at(&self, time: u64) -> u64 {
+        let elapsed = time.saturating_sub(self.start);
+
+        if elapsed < self.cliff {
+            return 0;
+        }
+
+        if elapsed >= self.duration {
+            return self.total_shares;
+        }
+
+        (self.total_shares as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+}
+
+// Keyed by stock_id
+pub const VESTINGS: Map<&[u8], Vesting> = Map::new("vestings");
+
+// The hidden part of a sealed bid. Its Bid holds no price or shares until it is revealed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SealedBid {
+    pub bid_id: u64,
+    // Hex encoded sha256 of "{bidder}:{price_per_share}:{shares}:{salt}"
+    pub commitment: String,
+    // Funds sent with the commitment, they have to cover the revealed bid
+    pub deposit: u128,
+    pub revealed: bool,
+}
+
+// Keyed by bid id
+pub const SEALED_BIDS: Map<&[u8], SealedBid> = Map::new("sealed_bids");
+
+// Revealed sealed bids keyed by (stock_id, u128::MAX - price_per_share, bid_id),
+// ascending iteration walks the highest priced (then earliest committed) bid first.
+// Bids are removed once they have been allocated or settled.
+pub const REVEALED_BIDS: Map<(u64, u128, u64), ()> = Map::new("revealed_bids");
+
+// Profile shown for a stock, set and edited by its influencer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct StockMetadata {
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    // https:// or ipfs:// link to the stock's image or avatar
+    pub image_uri: Option<String>,
+    #[serde(default)]
+    pub socials: Vec<SocialHandle>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SocialHandle {
+    // e.g. "x", "youtube", "instagram"
+    pub platform: String,
+    pub handle: String,
 }
 
 // Index for Stocks
@@ -48,6 +364,53 @@ pub const STOCK_INDEXES: StockIndexes = StockIndexes {
 pub const STOCKS: IndexedMap<&[u8], Stock, StockIndexes> = IndexedMap::new("stocks", STOCK_INDEXES);
 pub const STOCK_COUNT: Item<u64> = Item::new("stock_count");
 
+// Progress of an auction that is being settled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct Settlement {
+    // Last bid settled, the next batch resumes after it
+    pub last_bid_id: Option<u64>,
+    pub settled_bids: u64,
+    // Set while the revealed bids of a sealed bid auction are being allocated,
+    // which comes before any bid is settled
+    #[serde(default)]
+    pub allocating: bool,
+    #[serde(default)]
+    pub allocated_bids: u64,
+    // Set when the auction raised less than its funding goal,
+    // every bid is then refunded instead of settled
+    #[serde(default)]
+    pub goal_missed: bool,
+}
+
+// Settlements in progress keyed by stock_id
+pub const SETTLEMENTS: Map<&[u8], Settlement> = Map::new("settlement");
+
+// Stock ids keyed by their normalized ticker, a ticker belongs to one stock at a time
+pub const TICKERS: Map<&str, u64> = Map::new("tickers");
+
+// A ticker set aside by the owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TickerReservation {
+    // The only address that may create a stock with the ticker, nobody when not set
+    pub holder: Option<Addr>,
+}
+
+// Reservations keyed by normalized ticker
+pub const RESERVED_TICKERS: Map<&str, TickerReservation> = Map::new("reserved_tickers");
+
+// Royalty an influencer takes from secondary sales of their stock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct Royalty {
+    pub royalty_bps: u16,
+    // Paid to the influencer when not set
+    pub payout_address: Option<Addr>,
+    // Lifetime royalties paid
+    pub total_paid: u128,
+}
+
+// Royalties keyed by stock_id
+pub const ROYALTIES: Map<&[u8], Royalty> = Map::new("royalty");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Bid {
     pub id: u64,
@@ -57,16 +420,18 @@ pub struct Bid {
     pub shares_requested: u64,
     pub remaining_shares: u64, // Number of shares that haven't been outbid
     pub created_at: u64,
-    pub open: u8, // Whether the bid is has still open (has not been outbid)
+    pub open: bool, // Whether the bid is has still open (has not been outbid)
     pub active: bool, // Whether the stock is still in auction
-                  // (all inactive bids are closed)
+                    // (all inactive bids are closed)
 }
 
 // Index for Bids
 pub struct BidIndexes<'a> {
     pub stock_id: MultiIndex<'a, u64, Bid, &'a [u8]>,
     pub bidder: MultiIndex<'a, Addr, Bid, &'a [u8]>,
-    pub stock_open: MultiIndex<'a, (u64, u8), Bid, &'a [u8]>,
+    // open is stored as 0/1 in the key, open bids are ordered by price
+    pub stock_open: MultiIndex<'a, (u64, u8, u128), Bid, &'a [u8]>,
+    pub stock_bidder: MultiIndex<'a, (u64, Addr), Bid, &'a [u8]>,
 }
 
 impl IndexList<Bid> for BidIndexes<'_> {
@@ -75,6 +440,7 @@ impl IndexList<Bid> for BidIndexes<'_> {
             &self.stock_id as &dyn Index<Bid>,
             &self.bidder as &dyn Index<Bid>,
             &self.stock_open as &dyn Index<Bid>,
+            &self.stock_bidder as &dyn Index<Bid>,
         ];
         Box::new(v.into_iter())
     }
@@ -85,47 +451,62 @@ pub const BID_INDEXES: BidIndexes = BidIndexes {
     stock_id: MultiIndex::new(|_pk, bid| bid.stock_id, "bids", "bids__stock_id"),
     bidder: MultiIndex::new(|_pk, bid| bid.bidder.clone(), "bids", "bids__bidder"),
     stock_open: MultiIndex::new(
-        |_pk, bid| (bid.stock_id, bid.open),
+        |_pk, bid| (bid.stock_id, bid.open as u8, bid.price_per_share),
+        "bids",
+        "bids__stock_open_price",
+    ),
+    stock_bidder: MultiIndex::new(
+        |_pk, bid| (bid.stock_id, bid.bidder.clone()),
         "bids",
-        "bids__stock_open",
+        "bids__stock_bidder",
     ),
 };
 
 pub const BIDS: IndexedMap<&[u8], Bid, BidIndexes> = IndexedMap::new("bids", BID_INDEXES);
 pub const BID_COUNT: Item<u64> = Item::new("bid_count");
 
+// A holder's position in a stock, there is at most one per (stock_id, owner)
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Share {
-    pub id: u64,
     pub stock_id: u64,
     pub no_of_shares: u64,
     pub owner: Addr,
+    // Shares reserved by the owner's open sell orders
+    #[serde(default)]
+    pub locked_shares: u64,
+}
+
+impl Share {
+    // Shares that are free to be sold or transferred
+    pub fn available_shares(&self) -> u64 {
+        self.no_of_shares - self.locked_shares
+    }
 }
 
 // Index for Shares
 pub struct ShareIndexes<'a> {
-    pub stock_id: MultiIndex<'a, u64, Share, &'a [u8]>,
-    pub owner: MultiIndex<'a, Addr, Share, &'a [u8]>,
+    pub owner: MultiIndex<'a, Addr, Share, (u64, &'a Addr)>,
 }
 
 impl IndexList<Share> for ShareIndexes<'_> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Share>> + '_> {
-        let v = vec![
-            &self.stock_id as &dyn Index<Share>,
-            &self.owner as &dyn Index<Share>,
-        ];
+        let v = vec![&self.owner as &dyn Index<Share>];
         Box::new(v.into_iter())
     }
 }
 
 // Create indexes
 pub const SHARE_INDEXES: ShareIndexes = ShareIndexes {
-    stock_id: MultiIndex::new(|_pk, share| share.stock_id, "share", "share__stock_id"),
-    owner: MultiIndex::new(|_pk, share| share.owner.clone(), "share", "share__owner"),
+    owner: MultiIndex::new(
+        |_pk, share| share.owner.clone(),
+        "share_position",
+        "share_position__owner",
+    ),
 };
 
-pub const SHARES: IndexedMap<&[u8], Share, ShareIndexes> = IndexedMap::new("share", SHARE_INDEXES);
-pub const SHARE_COUNT: Item<u64> = Item::new("share_count");
+// Share positions keyed by (stock_id, owner)
+pub const SHARES: IndexedMap<(u64, &Addr), Share, ShareIndexes> =
+    IndexedMap::new("share_position", SHARE_INDEXES);
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct SellOrder {
@@ -173,6 +554,11 @@ pub const SELL_ORDERS: IndexedMap<&[u8], SellOrder, SellOrderIndexes> =
     IndexedMap::new("sell_order", SELL_ORDER_INDEXES);
 pub const SELL_ORDER_COUNT: Item<u64> = Item::new("sell_order_count");
 
+// Open sell orders keyed by (stock_id, price_per_share, order_id),
+// ascending iteration walks the cheapest (then oldest) order first.
+// Orders are removed once they are resolved.
+pub const OPEN_SELL_ORDERS: Map<(u64, u128, u64), ()> = Map::new("open_sell_orders");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct BuyOrder {
     pub id: u64,
@@ -219,6 +605,23 @@ pub const BUY_ORDERS: IndexedMap<&[u8], BuyOrder, BuyOrderIndexes> =
     IndexedMap::new("buy_order", BUY_ORDER_INDEXES);
 pub const BUY_ORDER_COUNT: Item<u64> = Item::new("buy_order_count");
 
+// Open buy orders keyed by (stock_id, u128::MAX - price_per_share, order_id),
+// ascending iteration walks the highest priced (then oldest) order first.
+// Orders are removed once they are resolved.
+pub const OPEN_BUY_ORDERS: Map<(u64, u128, u64), ()> = Map::new("open_buy_orders");
+
+// Funds held by the contract for the unfilled part of each open buy order
+pub const BUY_ORDER_ESCROWS: Map<&[u8], u128> = Map::new("buy_order_escrow");
+
+// Funds held for each stock's auction until it is settled
+pub const AUCTION_ESCROWS: Map<&[u8], u128> = Map::new("auction_escrow");
+
+// Sum of all funds the contract holds in escrow
+pub const TOTAL_ESCROW: Item<u128> = Item::new("total_escrow");
+
+// Protocol fees collected and not yet withdrawn
+pub const ACCRUED_FEES: Item<u128> = Item::new("accrued_fees");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Sale {
     pub id: u64,
@@ -228,6 +631,12 @@ pub struct Sale {
     pub from: Addr,
     pub to: Addr,
     pub created_at: u64,
+    // Protocol fee taken from the seller's proceeds
+    #[serde(default)]
+    pub fee: u128,
+    // Influencer royalty taken from the seller's proceeds
+    #[serde(default)]
+    pub royalty: u128,
 }
 
 // Index for Sales
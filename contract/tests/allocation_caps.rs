@@ -0,0 +1,203 @@
+use common::{place_bid, setup_app, setup_stock, start_auction, AuctionParams};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetMinimumBidPriceResponse, InstantiateMsg, QueryMsg},
+    state::DutchAuction,
+};
+
+mod common;
+
+// Start an auction that caps each address at 300_000 shares, in bids of at least 10_000
+fn setup_capped_auction(app: &mut App, vault: &Addr) -> Addr {
+    let (contract_addr, influencer) = setup_stock(app, vault, &InstantiateMsg::default());
+
+    let res = start_auction(
+        app,
+        &contract_addr,
+        &influencer,
+        AuctionParams {
+            max_shares_per_address: Some(300_000),
+            min_shares_per_bid: Some(10_000),
+            presale: None,
+            retained_allocation: None,
+            ..AuctionParams::default()
+        },
+    );
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "max_shares_per_address" && attr.value == "300000"));
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "min_shares_per_bid" && attr.value == "10000"));
+
+    contract_addr
+}
+
+fn remaining_allocation(app: &App, contract_addr: &Addr, bidder: Option<&Addr>) -> Option<u64> {
+    let res: GetMinimumBidPriceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetMinimumBidPrice {
+                stock_id: 1,
+                shares_requested: 10_000,
+                bidder: bidder.cloned(),
+            },
+        )
+        .unwrap();
+
+    res.remaining_allocation
+}
+
+#[test]
+fn test_bids_are_capped_per_address() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = setup_capped_auction(&mut app, &vault);
+
+    let bidder = app.api().addr_make("bidder");
+
+    assert_eq!(
+        remaining_allocation(&app, &contract_addr, None),
+        Some(300_000)
+    );
+
+    // Too small a bid
+    let err = place_bid(&mut app, &vault, &contract_addr, &bidder, 1, 5_000).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bids must be for at least 10000 shares"));
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder, 1, 200_000).unwrap();
+
+    assert_eq!(
+        remaining_allocation(&app, &contract_addr, Some(&bidder)),
+        Some(100_000)
+    );
+
+    // The cap counts every open bid of the address
+    let err = place_bid(&mut app, &vault, &contract_addr, &bidder, 1, 200_000).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bidder can only bid for 100000 more shares of stock 1"));
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder, 1, 100_000).unwrap();
+
+    assert_eq!(
+        remaining_allocation(&app, &contract_addr, Some(&bidder)),
+        Some(0)
+    );
+}
+
+#[test]
+fn test_outbid_shares_free_up_allocation() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = setup_capped_auction(&mut app, &vault);
+
+    let bidder1 = app.api().addr_make("bidder1");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 1, 300_000).unwrap();
+
+    // Raising a bid's shares counts against the cap too
+    app.send_tokens(vault.clone(), bidder1.clone(), &coins(10_000, DENOM))
+        .unwrap();
+
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::IncreaseBid {
+                bid_id: 2,
+                price_per_share: None,
+                additional_shares: Some(10_000),
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bidder can only bid for 0 more shares of stock 1"));
+
+    // The last of these takes 200_000 of bidder1's shares once the influencer's are gone
+    for name in ["bidder2", "bidder3", "bidder4"] {
+        let bidder = app.api().addr_make(name);
+        place_bid(&mut app, &vault, &contract_addr, &bidder, 2, 300_000).unwrap();
+    }
+
+    assert_eq!(
+        remaining_allocation(&app, &contract_addr, Some(&bidder1)),
+        Some(200_000)
+    );
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 3, 200_000).unwrap();
+}
+
+#[test]
+fn test_dutch_purchases_are_capped_per_address() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    start_auction(
+        &mut app,
+        &contract_addr,
+        &influencer,
+        AuctionParams {
+            dutch_auction: Some(DutchAuction {
+                start_price: 10,
+                floor_price: 5,
+                price_decrement: 1,
+                decrement_interval: 60 * 60 * 1000,
+            }),
+            max_shares_per_address: Some(300_000),
+            min_shares_per_bid: Some(10_000),
+            presale: None,
+            retained_allocation: None,
+            ..AuctionParams::default()
+        },
+    );
+
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(4_000_000, DENOM))
+        .unwrap();
+
+    let mut buy = |app: &mut App, shares: u64| {
+        app.execute_contract(
+            buyer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::BuyAuctionShares {
+                stock_id: 1,
+                shares,
+            },
+            &coins(shares as u128 * 10, DENOM),
+        )
+    };
+
+    let err = buy(&mut app, 5_000).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Purchases must be for at least 10000 shares"));
+
+    buy(&mut app, 200_000).unwrap();
+
+    // Shares already bought count against the cap
+    assert_eq!(
+        remaining_allocation(&app, &contract_addr, Some(&buyer)),
+        Some(100_000)
+    );
+
+    let err = buy(&mut app, 200_000).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bidder can only bid for 100000 more shares of stock 1"));
+
+    buy(&mut app, 100_000).unwrap();
+}
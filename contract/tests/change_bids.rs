@@ -0,0 +1,291 @@
+use common::{balance, place_bid, setup_app, setup_auction, AuctionParams};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetBidByIdResponse, GetMinimumBidPriceResponse, InstantiateMsg, QueryMsg},
+};
+
+mod common;
+
+const HOUR: u64 = 60 * 60;
+
+fn get_bid(app: &App, contract_addr: &Addr, bid_id: u64) -> GetBidByIdResponse {
+    app.wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetBidById { bid_id })
+        .unwrap()
+}
+
+#[test]
+fn test_cancel_bid() {
+    let (mut app, vault) = setup_app();
+
+    // No cancelling in the last hour
+    let (contract_addr, influencer) = setup_auction(
+        &mut app,
+        &vault,
+        &InstantiateMsg {
+            bid_lock_window: Some(HOUR * 1000),
+            ..InstantiateMsg::default()
+        },
+        AuctionParams::default(),
+    );
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 100_000).unwrap();
+
+    let cancel = |app: &mut App, sender: &Addr, bid_id: u64| {
+        app.execute_contract(
+            sender.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CancelBid { bid_id },
+            &[],
+        )
+    };
+
+    let err = cancel(&mut app, &bidder2, 2).unwrap_err();
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    let err = cancel(&mut app, &influencer, 1).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("The opening bid can't be changed"));
+
+    cancel(&mut app, &bidder1, 2).unwrap();
+
+    assert_eq!(balance(&app, &bidder1), 1_000_000);
+
+    let res = get_bid(&app, &contract_addr, 2);
+    assert!(!res.bid.open);
+    assert_eq!(res.bid.remaining_shares, 0);
+
+    // The shares are back with the influencer's opening bid
+    let res = get_bid(&app, &contract_addr, 1);
+    assert!(res.bid.open);
+    assert_eq!(res.bid.remaining_shares, 1_000_000);
+
+    let res: GetMinimumBidPriceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetMinimumBidPrice {
+                stock_id: 1,
+                shares_requested: 1_000_000,
+                bidder: None,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.min_price, "1");
+
+    let err = cancel(&mut app, &bidder1, 2).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bid 2 is no longer open"));
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 100_000).unwrap();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(23 * HOUR + 30 * 60);
+    });
+
+    let err = cancel(&mut app, &bidder1, 3).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bids can't be cancelled within 3600000 milliseconds of the auction's end"));
+}
+
+#[test]
+fn test_increase_bid() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, _) = setup_auction(
+        &mut app,
+        &vault,
+        &InstantiateMsg::default(),
+        AuctionParams::default(),
+    );
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    // Together they cover the whole supply
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 500_000).unwrap();
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 12, 500_000).unwrap();
+
+    app.send_tokens(vault.clone(), bidder1.clone(), &coins(6_000_000, DENOM))
+        .unwrap();
+
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::IncreaseBid {
+                bid_id: 2,
+                price_per_share: Some(9),
+                additional_shares: None,
+            },
+            &coins(1_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bid price can't be lowered"));
+
+    // 700_000 @ 15 costs 10_500_000, 5_000_000 of which is already held
+    let res = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::IncreaseBid {
+                bid_id: 2,
+                price_per_share: Some(15),
+                additional_shares: Some(200_000),
+            },
+            &coins(6_000_000, DENOM),
+        )
+        .unwrap();
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "charged" && attr.value == "5500000"));
+
+    // The excess is sent back
+    assert_eq!(balance(&app, &bidder1), 500_000);
+
+    let res = get_bid(&app, &contract_addr, 2);
+    assert_eq!(res.bid.price_per_share, 15);
+    assert_eq!(res.bid.shares_requested, 700_000);
+    assert_eq!(res.bid.remaining_shares, 700_000);
+
+    // bidder2 is outbid for the added shares
+    let res = get_bid(&app, &contract_addr, 3);
+    assert_eq!(res.bid.remaining_shares, 300_000);
+    assert_eq!(balance(&app, &bidder2), 2_400_000);
+
+    // Both bids are held until the auction ends
+    assert_eq!(balance(&app, &contract_addr), 10_500_000 + 3_600_000);
+}
+
+#[test]
+fn test_increase_bid_outbids_the_next_bid_up() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, _) = setup_auction(
+        &mut app,
+        &vault,
+        &InstantiateMsg::default(),
+        AuctionParams::default(),
+    );
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 500_000).unwrap();
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 20, 500_000).unwrap();
+
+    app.send_tokens(vault.clone(), bidder1.clone(), &coins(6_000_000, DENOM))
+        .unwrap();
+
+    // The added shares can only come from bidder2's bid, so they must outbid its price
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::IncreaseBid {
+                bid_id: 2,
+                price_per_share: Some(11),
+                additional_shares: Some(100),
+            },
+            &coins(500_100 * 11, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bid price too low. Minimum price is 21"));
+
+    let res = get_bid(&app, &contract_addr, 3);
+    assert_eq!(res.bid.remaining_shares, 500_000);
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::IncreaseBid {
+            bid_id: 2,
+            price_per_share: Some(21),
+            additional_shares: Some(100),
+        },
+        &coins(500_100 * 21 - 5_000_000, DENOM),
+    )
+    .unwrap();
+
+    let res = get_bid(&app, &contract_addr, 3);
+    assert_eq!(res.bid.remaining_shares, 499_900);
+    assert_eq!(balance(&app, &bidder2), 2_000);
+}
+
+#[test]
+fn test_bids_can_be_cancelled_but_not_increased_during_a_halt() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, _) = setup_auction(
+        &mut app,
+        &vault,
+        &InstantiateMsg::default(),
+        AuctionParams::default(),
+    );
+
+    let bidder1 = app.api().addr_make("bidder1");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 100_000).unwrap();
+
+    app.send_tokens(vault.clone(), bidder1.clone(), &coins(1_000_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Halt {
+            stock_id: Some(1),
+            reason: "Investigating".to_owned(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::IncreaseBid {
+                bid_id: 2,
+                price_per_share: Some(11),
+                additional_shares: None,
+            },
+            &coins(100_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Trading of stock 1 is halted"));
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelBid { bid_id: 2 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, &bidder1), 2_000_000);
+    assert!(!get_bid(&app, &contract_addr, 2).bid.open);
+}
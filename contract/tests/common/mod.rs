@@ -1,6 +1,10 @@
 use cosmwasm_std::{coins, Addr, Empty};
-use cw_multi_test::{App, Contract, ContractWrapper};
-use influencer_stocks::contract::{self, DENOM};
+use cw_multi_test::{error::AnyResult, App, AppResponse, Contract, ContractWrapper, Executor};
+use influencer_stocks::{
+    contract::{self, DENOM},
+    msg::{ExecuteMsg, GetShareResponse, InstantiateMsg, QueryMsg},
+    state::{DutchAuction, Presale, RetainedAllocation, SealedBidAuction},
+};
 
 // Create test environment with initial balances
 pub fn setup_app() -> (App, Addr) {
@@ -22,6 +26,256 @@ pub fn setup_app() -> (App, Addr) {
 
 // Helper function to get contract code
 pub fn contract_code() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(contract::execute, contract::instantiate, contract::query);
+    let contract = ContractWrapper::new(contract::execute, contract::instantiate, contract::query)
+        .with_migrate(contract::migrate);
     Box::new(contract)
 }
+
+// Instantiate the contract from `msg`
+#[allow(dead_code)]
+pub fn instantiate(app: &mut App, vault: &Addr, msg: &InstantiateMsg) -> Addr {
+    let code_id = app.store_code(contract_code());
+    app.instantiate_contract(code_id, vault.clone(), msg, &[], "Influencer Stocks", None)
+        .unwrap()
+}
+
+// Instantiate the contract and take a stock through its auction so that it is in sale.
+// bidder1 ends up with 50_000 shares @ 10 and bidder2 with 950_000 shares @ 11.
+#[allow(dead_code)]
+pub fn setup_stock_in_sale(app: &mut App, vault: &Addr) -> (Addr, u64, Addr, Addr, Addr) {
+    setup_stock_in_sale_with(app, vault, &InstantiateMsg::default())
+}
+
+// Same as setup_stock_in_sale, with the contract instantiated from `msg`
+#[allow(dead_code)]
+pub fn setup_stock_in_sale_with(
+    app: &mut App,
+    vault: &Addr,
+    msg: &InstantiateMsg,
+) -> (Addr, u64, Addr, Addr, Addr) {
+    let contract_addr = instantiate(app, vault, msg);
+
+    let influencer = app.api().addr_make("influencer");
+
+    let res = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateStock {
+                ticker: "TEST".to_owned(),
+                total_shares: None,
+                metadata: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let stock_id: u64 = res
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == "stock_id")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    app.send_tokens(vault.clone(), bidder1.clone(), &coins(1_000_000, DENOM))
+        .unwrap();
+
+    app.send_tokens(vault.clone(), bidder2.clone(), &coins(950_000 * 11, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::PlaceBid {
+            stock_id,
+            price_per_share: 10,
+            shares: 100_000,
+        },
+        &coins(1_000_000, DENOM),
+    )
+    .unwrap();
+
+    app.execute_contract(
+        bidder2.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::PlaceBid {
+            stock_id,
+            price_per_share: 11,
+            shares: 950_000,
+        },
+        &coins(950_000 * 11, DENOM),
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::EndAuction { stock_id },
+        &[],
+    )
+    .unwrap();
+
+    (contract_addr, stock_id, influencer, bidder1, bidder2)
+}
+
+// Settings of the auction started by start_auction, anything left unset takes its default
+#[allow(dead_code)]
+#[derive(Default)]
+pub struct AuctionParams {
+    pub start_at: Option<u64>,
+    pub duration: Option<u64>,
+    pub reserve_price: Option<u128>,
+    pub uniform_price: Option<bool>,
+    pub dutch_auction: Option<DutchAuction>,
+    pub sealed_bid: Option<SealedBidAuction>,
+    pub funding_goal: Option<u128>,
+    pub max_shares_per_address: Option<u64>,
+    pub min_shares_per_bid: Option<u64>,
+    pub presale: Option<Presale>,
+    pub retained_allocation: Option<RetainedAllocation>,
+}
+
+// Instantiate the contract from `msg` and create stock 1 of 1_000_000 shares.
+// Returns the contract and the influencer.
+#[allow(dead_code)]
+pub fn setup_stock(app: &mut App, vault: &Addr, msg: &InstantiateMsg) -> (Addr, Addr) {
+    let contract_addr = instantiate(app, vault, msg);
+
+    let influencer = app.api().addr_make("influencer");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "TEST".to_owned(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    (contract_addr, influencer)
+}
+
+// Start the auction of stock 1 with `params`
+#[allow(dead_code)]
+pub fn start_auction(
+    app: &mut App,
+    contract_addr: &Addr,
+    influencer: &Addr,
+    params: AuctionParams,
+) -> AppResponse {
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: params.start_at,
+            duration: params.duration,
+            reserve_price: params.reserve_price,
+            uniform_price: params.uniform_price,
+            dutch_auction: params.dutch_auction,
+            sealed_bid: params.sealed_bid,
+            funding_goal: params.funding_goal,
+            max_shares_per_address: params.max_shares_per_address,
+            min_shares_per_bid: params.min_shares_per_bid,
+            presale: params.presale,
+            retained_allocation: params.retained_allocation,
+        },
+        &[],
+    )
+    .unwrap()
+}
+
+// Same as setup_stock, with the stock's auction started with `params`.
+// The influencer's opening bid is bid 1.
+#[allow(dead_code)]
+pub fn setup_auction(
+    app: &mut App,
+    vault: &Addr,
+    msg: &InstantiateMsg,
+    params: AuctionParams,
+) -> (Addr, Addr) {
+    let (contract_addr, influencer) = setup_stock(app, vault, msg);
+    start_auction(app, &contract_addr, &influencer, params);
+
+    (contract_addr, influencer)
+}
+
+// Fund `bidder` from the vault and bid `price` per share for `shares` of stock 1
+#[allow(dead_code)]
+pub fn place_bid(
+    app: &mut App,
+    vault: &Addr,
+    contract_addr: &Addr,
+    bidder: &Addr,
+    price: u128,
+    shares: u64,
+) -> AnyResult<AppResponse> {
+    let amount = price * shares as u128;
+
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(amount, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::PlaceBid {
+            stock_id: 1,
+            price_per_share: price,
+            shares,
+        },
+        &coins(amount, DENOM),
+    )
+}
+
+#[allow(dead_code)]
+pub fn balance(app: &App, addr: &Addr) -> u128 {
+    app.wrap()
+        .query_balance(addr.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+// Shares `owner` holds in stock 1, 0 when they have no position
+#[allow(dead_code)]
+pub fn get_shares(app: &App, contract_addr: &Addr, owner: &Addr) -> u64 {
+    app.wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetShare {
+                stock_id: 1,
+                owner: owner.clone(),
+            },
+        )
+        .map_or(0, |res: GetShareResponse| res.share.no_of_shares)
+}
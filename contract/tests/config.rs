@@ -0,0 +1,178 @@
+use common::{contract_code, instantiate, setup_app};
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{
+        ExecuteMsg, GetConfigResponse, GetStockByIdResponse, InstantiateMsg, QueryMsg,
+        UpdateConfigMsg,
+    },
+};
+
+mod common;
+
+#[test]
+fn test_instantiate_with_config() {
+    let (mut app, vault) = setup_app();
+
+    // Defaults
+    let contract_addr = instantiate(&mut app, &vault, &InstantiateMsg::default());
+
+    let res: GetConfigResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetConfig {})
+        .unwrap();
+
+    assert_eq!(res.config.denom, DENOM);
+    assert_eq!(res.config.total_shares, 1_000_000);
+    assert_eq!(res.config.auction_duration, 24 * 60 * 60 * 1000);
+    assert_eq!(res.config.min_bid_increment, 1);
+
+    // Custom values are used for new stocks and auctions
+    let contract_addr = instantiate(
+        &mut app,
+        &vault,
+        &InstantiateMsg {
+            denom: Some(DENOM.to_owned()),
+            total_shares: Some(500_000),
+            auction_duration: Some(60 * 60 * 1000),
+            min_bid_increment: Some(5),
+            ..InstantiateMsg::default()
+        },
+    );
+
+    let influencer = app.api().addr_make("influencer");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "TEST".to_owned(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetStockById { stock_id: 1 })
+        .unwrap();
+
+    assert_eq!(res.stock.total_shares, 500_000);
+    assert_eq!(
+        res.stock.auction_end.unwrap() - res.stock.auction_start.unwrap(),
+        60 * 60 * 1000
+    );
+
+    // Invalid values are rejected
+    let code_id = app.store_code(contract_code());
+    app.instantiate_contract(
+        code_id,
+        vault.clone(),
+        &InstantiateMsg {
+            total_shares: Some(0),
+            ..InstantiateMsg::default()
+        },
+        &[],
+        "Influencer Stocks",
+        None,
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_update_config() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate(&mut app, &vault, &InstantiateMsg::default());
+
+    // Only the owner may update the config
+    let err = app
+        .execute_contract(
+            app.api().addr_make("random"),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                total_shares: Some(2_000_000),
+                ..UpdateConfigMsg::default()
+            }),
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+            total_shares: Some(2_000_000),
+            max_total_shares: Some(2_000_000),
+            min_bid_increment: Some(10),
+            ..UpdateConfigMsg::default()
+        }),
+        &[],
+    )
+    .unwrap();
+
+    let res: GetConfigResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {})
+        .unwrap();
+
+    assert_eq!(res.config.denom, DENOM);
+    assert_eq!(res.config.total_shares, 2_000_000);
+    assert_eq!(res.config.min_total_shares, 1_000_000);
+    assert_eq!(res.config.max_total_shares, 2_000_000);
+    assert_eq!(res.config.auction_duration, 24 * 60 * 60 * 1000);
+    assert_eq!(res.config.min_bid_increment, 10);
+
+    // Invalid values are rejected
+    let err = app
+        .execute_contract(
+            vault.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                total_shares: Some(3_000_000),
+                ..UpdateConfigMsg::default()
+            }),
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Total shares must be between 1000000 and 2000000"));
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+            denom: Some("".to_owned()),
+            ..UpdateConfigMsg::default()
+        }),
+        &[],
+    )
+    .unwrap_err();
+}
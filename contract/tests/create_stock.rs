@@ -3,8 +3,11 @@ use cosmwasm_std::Addr;
 use cw_multi_test::Executor;
 use influencer_stocks::{
     contract::execute::stocks::TOTAL_SHARES,
-    msg::{ExecuteMsg, GetStockByIdResponse, InstantiateMsg, QueryMsg},
-    state::Stock,
+    msg::{
+        ExecuteMsg, GetBidsResponse, GetMinimumBidPriceResponse, GetStockByIdResponse,
+        InstantiateMsg, QueryMsg,
+    },
+    state::{Stock, StockMetadata},
 };
 
 mod common;
@@ -20,7 +23,7 @@ fn test_create_and_query_stock_by_id() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -33,6 +36,8 @@ fn test_create_and_query_stock_by_id() {
     let ticker = "INFL1".to_string();
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.clone(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -69,8 +74,24 @@ fn test_create_and_query_stock_by_id() {
                 total_shares: TOTAL_SHARES,
                 auction_start: None,
                 auction_end: None,
+                max_auction_end: None,
+                reserve_price: 0,
+                uniform_price: false,
+                clearing_price: None,
+                dutch_auction: None,
+                sealed_bid: None,
+                funding_goal: None,
+                max_shares_per_address: None,
+                min_shares_per_bid: None,
+                presale: None,
+                retained_allocation: None,
+                amount_raised: 0,
+                opening_bid_id: None,
                 marked_as_active_auction: false,
-                created_at: response.clone().stock.created_at
+                settling: false,
+                created_at: response.clone().stock.created_at,
+                delisted_at: None,
+                metadata: StockMetadata::default(),
             }
         },
         response
@@ -88,7 +109,7 @@ fn test_create_multiple_stocks() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -108,6 +129,8 @@ fn test_create_multiple_stocks() {
     for (i, (influencer, ticker)) in influencers.iter().zip(tickers.iter()).enumerate() {
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.to_string(),
+            total_shares: None,
+            metadata: None,
         };
 
         let res = app
@@ -133,3 +156,130 @@ fn test_create_multiple_stocks() {
         assert_eq!(response.stock.influencer, influencer.clone());
     }
 }
+
+#[test]
+fn test_create_stock_with_chosen_supply() {
+    let (mut app, vault) = setup_app();
+
+    let code_id = app.store_code(contract_code());
+
+    // Influencers may pick between 10_000 and 5_000_000 shares
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg {
+                min_total_shares: Some(10_000),
+                max_total_shares: Some(5_000_000),
+                ..InstantiateMsg::default()
+            },
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer = Addr::unchecked("influencer");
+
+    // Out of bounds supplies are rejected
+    for total_shares in [9_999, 5_000_001] {
+        let err = app
+            .execute_contract(
+                influencer.clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::CreateStock {
+                    ticker: "INFL".to_string(),
+                    total_shares: Some(total_shares),
+                    metadata: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+
+        assert!(err
+            .root_cause()
+            .to_string()
+            .contains("Total shares must be between 10000 and 5000000"));
+    }
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "INFL".to_string(),
+            total_shares: Some(20_000),
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.total_shares, 20_000);
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The influencer's opening bid covers the chosen supply
+    let res: GetBidsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetOpenBidsByStock { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.bids.len(), 1);
+    assert_eq!(res.bids[0].remaining_shares, 20_000);
+
+    let res: GetMinimumBidPriceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetMinimumBidPrice {
+                stock_id: 1,
+                shares_requested: 20_000,
+                bidder: None,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.min_price, "1");
+
+    // Bids can't ask for more than the supply
+    app.wrap()
+        .query_wasm_smart::<GetMinimumBidPriceResponse>(
+            contract_addr.clone(),
+            &QueryMsg::GetMinimumBidPrice {
+                stock_id: 1,
+                shares_requested: 20_001,
+                bidder: None,
+            },
+        )
+        .unwrap_err();
+}
@@ -0,0 +1,275 @@
+use common::{balance, get_shares, setup_app, setup_stock, start_auction, AuctionParams};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetDutchAuctionResponse, InstantiateMsg, QueryMsg},
+    state::DutchAuction,
+};
+
+mod common;
+
+const HOUR: u64 = 60 * 60;
+
+// Price starts at 100 and drops by 10 every hour down to 40
+fn price_schedule() -> DutchAuction {
+    DutchAuction {
+        start_price: 100,
+        floor_price: 40,
+        price_decrement: 10,
+        decrement_interval: HOUR * 1000,
+    }
+}
+
+fn start_dutch_auction(app: &mut App, contract_addr: &Addr, influencer: &Addr) {
+    start_auction(
+        app,
+        contract_addr,
+        influencer,
+        AuctionParams {
+            dutch_auction: Some(price_schedule()),
+            ..AuctionParams::default()
+        },
+    );
+}
+
+fn get_dutch_auction(app: &App, contract_addr: &Addr) -> GetDutchAuctionResponse {
+    app.wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetDutchAuction { stock_id: 1 },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_dutch_auction_price_decays() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    start_dutch_auction(&mut app, &contract_addr, &influencer);
+
+    let buyer1 = app.api().addr_make("buyer1");
+    let buyer2 = app.api().addr_make("buyer2");
+
+    app.send_tokens(vault.clone(), buyer1.clone(), &coins(10_000_000, DENOM))
+        .unwrap();
+    app.send_tokens(vault.clone(), buyer2.clone(), &coins(20_000_000, DENOM))
+        .unwrap();
+
+    let res = get_dutch_auction(&app, &contract_addr);
+    assert_eq!(res.price_per_share, "100");
+    assert_eq!(res.remaining_shares, 1_000_000);
+
+    // Bids aren't taken
+    let err = app
+        .execute_contract(
+            buyer1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::PlaceBid {
+                stock_id: 1,
+                price_per_share: 100,
+                shares: 1_000,
+            },
+            &coins(100_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Stock 1 is sold in a Dutch auction"));
+
+    app.execute_contract(
+        buyer1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::BuyAuctionShares {
+            stock_id: 1,
+            shares: 100_000,
+        },
+        &coins(10_000_000, DENOM),
+    )
+    .unwrap();
+
+    // The shares and the influencer's pay change hands right away
+    assert_eq!(get_shares(&app, &contract_addr, &buyer1), 100_000);
+    assert_eq!(balance(&app, &influencer), 10_000_000);
+
+    // Three hours in the price is down to 70
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(3 * HOUR);
+    });
+
+    let res = get_dutch_auction(&app, &contract_addr);
+    assert_eq!(res.price_per_share, "70");
+    assert_eq!(res.remaining_shares, 900_000);
+
+    // Too little for the current price
+    let err = app
+        .execute_contract(
+            buyer2.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::BuyAuctionShares {
+                stock_id: 1,
+                shares: 200_000,
+            },
+            &coins(13_000_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Insufficient funds sent. Expected 14000000, got 13000000"));
+
+    // What is sent above the price is sent back
+    app.execute_contract(
+        buyer2.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::BuyAuctionShares {
+            stock_id: 1,
+            shares: 200_000,
+        },
+        &coins(20_000_000, DENOM),
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, &buyer2), 6_000_000);
+    assert_eq!(get_shares(&app, &contract_addr, &buyer2), 200_000);
+
+    // The price doesn't drop below the floor
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(10 * HOUR);
+    });
+
+    let res = get_dutch_auction(&app, &contract_addr);
+    assert_eq!(res.price_per_share, "40");
+    assert_eq!(res.remaining_shares, 700_000);
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * HOUR);
+    });
+
+    app.execute_contract(
+        buyer1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::SettleAuction {
+            stock_id: 1,
+            limit: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(get_shares(&app, &contract_addr, &buyer1), 100_000);
+    assert_eq!(get_shares(&app, &contract_addr, &buyer2), 200_000);
+
+    // The unsold shares go back to the influencer
+    assert_eq!(get_shares(&app, &contract_addr, &influencer), 700_000);
+    assert_eq!(balance(&app, &influencer), 24_000_000);
+
+    let res = get_dutch_auction(&app, &contract_addr);
+    assert_eq!(res.remaining_shares, 0);
+}
+
+#[test]
+fn test_dutch_auction_sells_out() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    // The floor can't be above the start price
+    let err = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::StartAuction {
+                stock_id: 1,
+                start_at: None,
+                duration: None,
+                reserve_price: None,
+                uniform_price: None,
+                dutch_auction: Some(DutchAuction {
+                    floor_price: 200,
+                    ..price_schedule()
+                }),
+                sealed_bid: None,
+                funding_goal: None,
+                max_shares_per_address: None,
+                min_shares_per_bid: None,
+                presale: None,
+                retained_allocation: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Floor price cannot be above the start price"));
+
+    start_dutch_auction(&mut app, &contract_addr, &influencer);
+
+    let buyer = app.api().addr_make("buyer");
+    let keeper = app.api().addr_make("keeper");
+
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(200_000_000, DENOM))
+        .unwrap();
+
+    let err = app
+        .execute_contract(
+            buyer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::BuyAuctionShares {
+                stock_id: 1,
+                shares: 1_000_001,
+            },
+            &coins(200_000_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Only 1000000 shares are left"));
+
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::BuyAuctionShares {
+            stock_id: 1,
+            shares: 1_000_000,
+        },
+        &coins(100_000_000, DENOM),
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            buyer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::BuyAuctionShares {
+                stock_id: 1,
+                shares: 1,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Only 0 shares are left"));
+
+    // Sold out, so anyone can settle it before the end
+    app.execute_contract(
+        keeper.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::EndAuction { stock_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(get_shares(&app, &contract_addr, &buyer), 1_000_000);
+    assert_eq!(balance(&app, &influencer), 100_000_000);
+}
@@ -4,8 +4,8 @@ use cw_multi_test::Executor;
 use influencer_stocks::{
     contract::DENOM,
     msg::{
-        ExecuteMsg, GetBidsResponse, GetSharesResponse, GetStockByIdResponse, InstantiateMsg,
-        QueryMsg,
+        ExecuteMsg, GetBidsResponse, GetShareResponse, GetSharesResponse, GetStockByIdResponse,
+        InstantiateMsg, QueryMsg,
     },
 };
 
@@ -21,7 +21,7 @@ fn test_end_auction_success() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -34,6 +34,8 @@ fn test_end_auction_success() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -52,7 +54,20 @@ fn test_end_auction_success() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -108,7 +123,7 @@ fn test_place_bids_and_end_auction_success() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -120,6 +135,8 @@ fn test_place_bids_and_end_auction_success() {
 
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_owned(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -138,7 +155,20 @@ fn test_place_bids_and_end_auction_success() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -194,7 +224,7 @@ fn test_place_bids_and_end_auction_success() {
         .unwrap();
 
     // Verify response attributes
-    let attrs = &res.events.last().unwrap().attributes;
+    let attrs = res.custom_attrs(1);
     assert!(attrs
         .iter()
         .any(|attr| attr.key == "action" && attr.value == "end_auction"));
@@ -229,7 +259,7 @@ fn test_place_bids_and_end_auction_success() {
     // Verfiy that all bids are closed and inactive
     for bid in bids_response.bids {
         assert!(!bid.active);
-        assert!(bid.open == 0);
+        assert!(!bid.open);
     }
 
     // Verify that shares were created with proper responses
@@ -251,6 +281,25 @@ fn test_place_bids_and_end_auction_success() {
 
     assert_eq!(shares[1].owner, bidder1);
     assert_eq!(shares[1].no_of_shares, 50_000);
+
+    // The influencer is paid for the winning bids when the auction ends
+    let influencer_balance = app
+        .wrap()
+        .query_balance(influencer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(influencer_balance, 50_000 * 10 + 950_000 * 11);
+
+    let contract_balance = app
+        .wrap()
+        .query_balance(contract_addr.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(contract_balance, 0);
 }
 
 #[test]
@@ -263,7 +312,7 @@ fn test_end_auction_by_owner() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -276,6 +325,8 @@ fn test_end_auction_by_owner() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -294,7 +345,20 @@ fn test_end_auction_by_owner() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -344,7 +408,7 @@ fn test_end_auction_unauthorized() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -357,6 +421,8 @@ fn test_end_auction_unauthorized() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -375,7 +441,20 @@ fn test_end_auction_unauthorized() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -411,7 +490,7 @@ fn test_end_auction_inactive() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -424,6 +503,8 @@ fn test_end_auction_inactive() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -468,7 +549,7 @@ fn test_end_auction_nonexistent_stock() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -496,3 +577,488 @@ fn test_end_auction_nonexistent_stock() {
         .to_string()
         .contains(&format!("Stock with id {}", nonexistent_stock_id)));
 }
+
+#[test]
+fn test_end_auction_merges_winning_bids_into_one_position() {
+    let (mut app, vault) = setup_app();
+
+    // Store the contract code and instantiate
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg::default(),
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer = app.api().addr_make("influencer");
+    let bidder = app.api().addr_make("bidder");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "TEST".to_owned(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(3_000_000, DENOM))
+        .unwrap();
+
+    // Two winning bids from the same bidder
+    for (price_per_share, shares) in [(10u128, 100_000u64), (20, 100_000)] {
+        app.execute_contract(
+            bidder.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::PlaceBid {
+                stock_id: 1,
+                price_per_share,
+                shares,
+            },
+            &coins(price_per_share * shares as u128, DENOM),
+        )
+        .unwrap();
+    }
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::EndAuction { stock_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetSharesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSharesByOwner {
+                owner: bidder.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.shares.len(), 1);
+    assert_eq!(res.shares[0].stock_id, 1);
+    assert_eq!(res.shares[0].no_of_shares, 200_000);
+
+    // The influencer keeps the unsold shares in their own position
+    let res: GetShareResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetShare {
+                stock_id: 1,
+                owner: influencer.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.share.no_of_shares, 800_000);
+}
+
+#[test]
+fn test_anyone_settles_after_auction_end() {
+    let (mut app, vault) = setup_app();
+
+    // 1% keeper reward
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg {
+                keeper_reward_bps: Some(100),
+                ..InstantiateMsg::default()
+            },
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer = app.api().addr_make("influencer");
+    let bidder = app.api().addr_make("bidder");
+    let keeper = app.api().addr_make("keeper");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "TEST".to_owned(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(1_000_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::PlaceBid {
+            stock_id: 1,
+            price_per_share: 10,
+            shares: 100_000,
+        },
+        &coins(1_000_000, DENOM),
+    )
+    .unwrap();
+
+    // The bid's funds are held until the auction is settled
+    let influencer_balance = app
+        .wrap()
+        .query_balance(influencer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(influencer_balance, 0);
+
+    // Outsiders can't end a running auction
+    let err = app
+        .execute_contract(
+            keeper.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::EndAuction { stock_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * 60 * 60 + 1);
+    });
+
+    // The auction is over but unsettled, so there's no trading yet
+    let err = app
+        .execute_contract(
+            bidder.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateSellOrder {
+                stock_id: 1,
+                price_per_share: 20,
+                shares: 1_000,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Stock is not in sale"));
+
+    // Now anyone can settle it
+    let res = app
+        .execute_contract(
+            keeper.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::EndAuction { stock_id: 1 },
+            &[],
+        )
+        .unwrap();
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "keeper_reward" && attr.value == "10000"));
+
+    let keeper_balance = app
+        .wrap()
+        .query_balance(keeper.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(keeper_balance, 10_000);
+
+    let influencer_balance = app
+        .wrap()
+        .query_balance(influencer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(influencer_balance, 1_000_000 - 10_000);
+
+    // Settling twice fails
+    let err = app
+        .execute_contract(
+            keeper.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::EndAuction { stock_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Stock has already been auctioned and in sale"));
+
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id: 1,
+            price_per_share: 20,
+            shares: 1_000,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_settle_auction_in_batches() {
+    let (mut app, vault) = setup_app();
+
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg::default(),
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer = app.api().addr_make("influencer");
+    let keeper = app.api().addr_make("keeper");
+    let bidders = ["bidder1", "bidder2", "bidder3"].map(|name| app.api().addr_make(name));
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "TEST".to_owned(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    for bidder in &bidders {
+        app.send_tokens(vault.clone(), bidder.clone(), &coins(1_000_000, DENOM))
+            .unwrap();
+
+        app.execute_contract(
+            bidder.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::PlaceBid {
+                stock_id: 1,
+                price_per_share: 10,
+                shares: 100_000,
+            },
+            &coins(1_000_000, DENOM),
+        )
+        .unwrap();
+    }
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * 60 * 60 + 1);
+    });
+
+    let err = app
+        .execute_contract(
+            keeper.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::SettleAuction {
+                stock_id: 1,
+                limit: Some(0),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Limit must be between 1 and 500"));
+
+    // The opening bid and the first bidder's bid
+    let res = app
+        .execute_contract(
+            keeper.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::SettleAuction {
+                stock_id: 1,
+                limit: Some(2),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "settled_bids" && attr.value == "2"));
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "complete" && attr.value == "false"));
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert!(res.stock.settling);
+    assert!(res.stock.marked_as_active_auction);
+
+    // No trading until the last batch is settled
+    let err = app
+        .execute_contract(
+            bidders[0].clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateSellOrder {
+                stock_id: 1,
+                price_per_share: 20,
+                shares: 1_000,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Stock is not in sale"));
+
+    let res = app
+        .execute_contract(
+            keeper.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::SettleAuction {
+                stock_id: 1,
+                limit: Some(2),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "settled_bids" && attr.value == "4"));
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "complete" && attr.value == "true"));
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert!(!res.stock.settling);
+    assert!(!res.stock.marked_as_active_auction);
+
+    for bidder in &bidders {
+        let res: GetShareResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetShare {
+                    stock_id: 1,
+                    owner: bidder.clone(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(res.share.no_of_shares, 100_000);
+    }
+
+    // Every bid went to the influencer
+    let influencer_balance = app
+        .wrap()
+        .query_balance(influencer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(influencer_balance, 3_000_000);
+}
@@ -0,0 +1,163 @@
+use common::{setup_app, setup_stock_in_sale};
+use cosmwasm_std::coins;
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetBuyOrderEscrowResponse, GetEscrowBalanceResponse, QueryMsg},
+};
+
+mod common;
+
+#[test]
+fn test_cancel_buy_order_refunds_escrow() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, _, _) = setup_stock_in_sale(&mut app, &vault);
+
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(50_000, DENOM))
+        .unwrap();
+
+    // No sell orders, so the whole order rests in the book
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateBuyOrder {
+            stock_id,
+            price_per_share: 10,
+            shares: 5_000,
+        },
+        &coins(50_000, DENOM),
+    )
+    .unwrap();
+
+    let res: GetBuyOrderEscrowResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetBuyOrderEscrow { buy_order_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.amount, "50000");
+
+    let res: GetEscrowBalanceResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEscrowBalance {})
+        .unwrap();
+
+    assert_eq!(res.total_escrow, "50000");
+    assert_eq!(res.contract_balance, "50000");
+    assert!(res.is_solvent);
+
+    // Cancel the order and get everything back
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelBuyOrder { buy_order_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let buyer_balance = app
+        .wrap()
+        .query_balance(buyer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(buyer_balance, 50_000);
+
+    let res: GetEscrowBalanceResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEscrowBalance {})
+        .unwrap();
+
+    assert_eq!(res.total_escrow, "0");
+    assert_eq!(res.contract_balance, "0");
+}
+
+#[test]
+fn test_cancel_partially_filled_buy_order() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, bidder1, _) = setup_stock_in_sale(&mut app, &vault);
+
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(100_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateBuyOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 5_000,
+        },
+        &coins(100_000, DENOM),
+    )
+    .unwrap();
+
+    // bidder1 fills 2_000 of the 5_000 shares
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 2_000,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetBuyOrderEscrowResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetBuyOrderEscrow { buy_order_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.amount, (3_000 * 20).to_string());
+
+    let res = app
+        .execute_contract(
+            buyer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CancelBuyOrder { buy_order_id: 1 },
+            &[],
+        )
+        .unwrap();
+
+    assert!(res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "refund" && attr.value == "60000"));
+
+    // buyer paid only for the 2_000 filled shares
+    let buyer_balance = app
+        .wrap()
+        .query_balance(buyer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(buyer_balance, 100_000 - 2_000 * 20);
+
+    let res: GetEscrowBalanceResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEscrowBalance {})
+        .unwrap();
+
+    assert_eq!(res.total_escrow, "0");
+    assert_eq!(res.contract_balance, "0");
+
+    // Cancelling again is rejected
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelBuyOrder { buy_order_id: 1 },
+        &[],
+    )
+    .unwrap_err();
+}
@@ -0,0 +1,222 @@
+use common::{setup_app, setup_stock_in_sale, setup_stock_in_sale_with};
+use cosmwasm_std::coins;
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetAccruedFeesResponse, GetSalesResponse, InstantiateMsg, QueryMsg},
+    state::Role,
+};
+
+mod common;
+
+#[test]
+fn test_auction_sales_pay_protocol_fee() {
+    let (mut app, vault) = setup_app();
+    let collector = app.api().addr_make("collector");
+
+    // 2.5% fee
+    let (contract_addr, stock_id, influencer, _, _) = setup_stock_in_sale_with(
+        &mut app,
+        &vault,
+        &InstantiateMsg {
+            fee_bps: Some(250),
+            fee_collector: Some(collector.to_string()),
+            ..InstantiateMsg::default()
+        },
+    );
+
+    let proceeds = 50_000 * 10 + 950_000 * 11;
+    let fee = proceeds * 250 / 10_000;
+
+    // The influencer is paid when the auction ends, less the fee on each sale
+    let influencer_balance = app
+        .wrap()
+        .query_balance(influencer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(influencer_balance, proceeds - fee);
+
+    let res: GetSalesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSalesByStock { stock_id },
+        )
+        .unwrap();
+
+    let sale = res
+        .sales
+        .iter()
+        .find(|sale| sale.price_per_share == 11)
+        .unwrap();
+    assert_eq!(sale.fee, 950_000 * 11 * 250 / 10_000);
+
+    let res: GetAccruedFeesResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetAccruedFees {})
+        .unwrap();
+
+    assert_eq!(res.amount, fee.to_string());
+    assert_eq!(res.fee_bps, 250);
+    assert_eq!(res.fee_collector, Some(collector.clone()));
+
+    // Only the owner withdraws
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::WithdrawFees {},
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::WithdrawFees {},
+        &[],
+    )
+    .unwrap();
+
+    let collector_balance = app
+        .wrap()
+        .query_balance(collector.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(collector_balance, fee);
+
+    let res: GetAccruedFeesResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetAccruedFees {})
+        .unwrap();
+
+    assert_eq!(res.amount, "0");
+}
+
+#[test]
+fn test_secondary_sales_pay_protocol_fee() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, bidder1, _) = setup_stock_in_sale(&mut app, &vault);
+
+    let fee_manager = app.api().addr_make("fee_manager");
+    let collector = app.api().addr_make("collector");
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            address: fee_manager.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Fees above 100% are rejected
+    app.execute_contract(
+        fee_manager.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateFeeConfig {
+            fee_bps: Some(10_001),
+            fee_collector: None,
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    // 1% fee
+    app.execute_contract(
+        fee_manager.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateFeeConfig {
+            fee_bps: Some(100),
+            fee_collector: Some(collector.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let seller_balance_before = app
+        .wrap()
+        .query_balance(bidder1.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 10_000,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(200_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::QuickBuy {
+            stock_id,
+            shares: 10_000,
+            slippage: 0,
+        },
+        &coins(200_000, DENOM),
+    )
+    .unwrap();
+
+    // The buyer pays the listed price, the seller gets it less 1%
+    let buyer_balance = app
+        .wrap()
+        .query_balance(buyer.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(buyer_balance, 0);
+
+    let seller_balance = app
+        .wrap()
+        .query_balance(bidder1.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(seller_balance - seller_balance_before, 200_000 - 2_000);
+
+    let res: GetAccruedFeesResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetAccruedFees {})
+        .unwrap();
+
+    assert_eq!(res.amount, "2000");
+
+    let res: GetSalesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSalesByStock { stock_id },
+        )
+        .unwrap();
+
+    let sale = res.sales.iter().find(|sale| sale.to == buyer).unwrap();
+    assert_eq!(sale.fee, 2_000);
+
+    // The fee manager can't withdraw, only the owner can
+    app.execute_contract(
+        fee_manager.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::WithdrawFees {},
+        &[],
+    )
+    .unwrap_err();
+}
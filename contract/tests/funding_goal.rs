@@ -0,0 +1,261 @@
+use common::{
+    balance, get_shares, place_bid, setup_app, setup_stock, start_auction, AuctionParams,
+};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetStockByIdResponse, InstantiateMsg, QueryMsg},
+    state::{DutchAuction, Stock},
+};
+
+mod common;
+
+fn start_auction_with_goal(
+    app: &mut App,
+    contract_addr: &Addr,
+    influencer: &Addr,
+    funding_goal: Option<u128>,
+    uniform_price: Option<bool>,
+) {
+    let res = start_auction(
+        app,
+        contract_addr,
+        influencer,
+        AuctionParams {
+            funding_goal,
+            uniform_price,
+            ..AuctionParams::default()
+        },
+    );
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs.iter().any(|attr| attr.key == "funding_goal"
+        && attr.value == funding_goal.map_or("none".to_string(), |goal| goal.to_string())));
+}
+
+fn end_auction(app: &mut App, contract_addr: &Addr, influencer: &Addr) -> bool {
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * 60 * 60 + 1);
+    });
+
+    let res = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::EndAuction { stock_id: 1 },
+            &[],
+        )
+        .unwrap();
+
+    res.custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "goal_missed" && attr.value == "true")
+}
+
+fn get_stock(app: &App, contract_addr: &Addr) -> Stock {
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    res.stock
+}
+
+#[test]
+fn test_missed_funding_goal_refunds_bidders_and_resets_stock() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    start_auction_with_goal(
+        &mut app,
+        &contract_addr,
+        &influencer,
+        Some(10_000_000),
+        None,
+    );
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 5, 100_000).unwrap();
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 8, 200_000).unwrap();
+
+    assert_eq!(get_stock(&app, &contract_addr).amount_raised, 2_100_000);
+
+    assert!(end_auction(&mut app, &contract_addr, &influencer));
+
+    // Every bidder gets back all they paid, nothing goes to the influencer
+    assert_eq!(balance(&app, &bidder1), 500_000);
+    assert_eq!(balance(&app, &bidder2), 1_600_000);
+    assert_eq!(balance(&app, &influencer), 0);
+    assert_eq!(balance(&app, &contract_addr), 0);
+
+    assert_eq!(get_shares(&app, &contract_addr, &bidder1), 0);
+    assert_eq!(get_shares(&app, &contract_addr, &bidder2), 0);
+
+    let stock = get_stock(&app, &contract_addr);
+    assert_eq!(stock.auction_start, None);
+    assert_eq!(stock.auction_end, None);
+    assert_eq!(stock.funding_goal, None);
+    assert_eq!(stock.amount_raised, 0);
+    assert!(!stock.marked_as_active_auction);
+    assert!(!stock.settling);
+
+    // The stock can be auctioned again, bids of the failed auction play no part in it
+    start_auction_with_goal(&mut app, &contract_addr, &influencer, None, None);
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 3, 100_000).unwrap();
+
+    assert!(!end_auction(&mut app, &contract_addr, &influencer));
+
+    assert_eq!(get_shares(&app, &contract_addr, &bidder1), 0);
+    assert_eq!(get_shares(&app, &contract_addr, &bidder2), 100_000);
+    assert_eq!(get_shares(&app, &contract_addr, &influencer), 900_000);
+    assert_eq!(balance(&app, &influencer), 300_000);
+}
+
+#[test]
+fn test_met_funding_goal_releases_proceeds_to_influencer() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    start_auction_with_goal(&mut app, &contract_addr, &influencer, Some(2_000_000), None);
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 100_000).unwrap();
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 10, 100_000).unwrap();
+
+    // The proceeds stay in escrow until the auction is settled
+    assert_eq!(balance(&app, &influencer), 0);
+    assert_eq!(balance(&app, &contract_addr), 2_000_000);
+
+    assert!(!end_auction(&mut app, &contract_addr, &influencer));
+
+    assert_eq!(balance(&app, &influencer), 2_000_000);
+    assert_eq!(balance(&app, &bidder1), 0);
+    assert_eq!(get_shares(&app, &contract_addr, &bidder1), 100_000);
+    assert_eq!(get_shares(&app, &contract_addr, &bidder2), 100_000);
+}
+
+#[test]
+fn test_amount_raised_follows_bid_changes() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    start_auction_with_goal(&mut app, &contract_addr, &influencer, Some(4_500_000), None);
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 500_000).unwrap();
+    assert_eq!(get_stock(&app, &contract_addr).amount_raised, 5_000_000);
+
+    // bidder1 is outbid for 100_000 of their shares
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 12, 600_000).unwrap();
+    assert_eq!(
+        get_stock(&app, &contract_addr).amount_raised,
+        4_000_000 + 7_200_000
+    );
+
+    app.execute_contract(
+        bidder2.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelBid { bid_id: 3 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(get_stock(&app, &contract_addr).amount_raised, 4_000_000);
+
+    // Raising the bid takes it past the goal
+    app.send_tokens(vault.clone(), bidder1.clone(), &coins(800_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::IncreaseBid {
+            bid_id: 2,
+            price_per_share: Some(12),
+            additional_shares: None,
+        },
+        &coins(800_000, DENOM),
+    )
+    .unwrap();
+
+    assert_eq!(get_stock(&app, &contract_addr).amount_raised, 4_800_000);
+
+    assert!(!end_auction(&mut app, &contract_addr, &influencer));
+    assert_eq!(get_shares(&app, &contract_addr, &bidder1), 400_000);
+}
+
+#[test]
+fn test_uniform_price_goal_counts_the_clearing_price() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    start_auction_with_goal(
+        &mut app,
+        &contract_addr,
+        &influencer,
+        Some(2_500_000),
+        Some(true),
+    );
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    // The bids add up to 4_000_000, but both winners pay the clearing price of 10
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 100_000).unwrap();
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 30, 100_000).unwrap();
+
+    assert!(end_auction(&mut app, &contract_addr, &influencer));
+
+    assert_eq!(balance(&app, &bidder1), 1_000_000);
+    assert_eq!(balance(&app, &bidder2), 3_000_000);
+    assert_eq!(balance(&app, &influencer), 0);
+}
+
+#[test]
+fn test_dutch_auction_cannot_have_funding_goal() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_stock(&mut app, &vault, &InstantiateMsg::default());
+
+    let err = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::StartAuction {
+                stock_id: 1,
+                start_at: None,
+                duration: None,
+                reserve_price: None,
+                uniform_price: None,
+                dutch_auction: Some(DutchAuction {
+                    start_price: 100,
+                    floor_price: 10,
+                    price_decrement: 10,
+                    decrement_interval: 60 * 60 * 1000,
+                }),
+                sealed_bid: None,
+                funding_goal: Some(1_000_000),
+                max_shares_per_address: None,
+                min_shares_per_bid: None,
+                presale: None,
+                retained_allocation: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("A Dutch auction can't have a funding goal"));
+}
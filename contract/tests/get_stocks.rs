@@ -1,4 +1,4 @@
-use common::{contract_code, setup_app};
+use common::{contract_code, setup_app, setup_stock_in_sale};
 use cosmwasm_std::Addr;
 use cw_multi_test::Executor;
 use influencer_stocks::msg::{
@@ -18,7 +18,7 @@ fn test_query_non_existent_stock() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -51,7 +51,7 @@ fn test_get_all_stocks_empty() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -64,6 +64,7 @@ fn test_get_all_stocks_empty() {
         in_auction: None,
         in_sale: None,
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -85,7 +86,7 @@ fn test_get_all_stocks() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -104,6 +105,8 @@ fn test_get_all_stocks() {
         let ticker = format!("INFL{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         app.execute_contract(influencer.clone(), contract_addr.clone(), &create_msg, &[])
@@ -116,6 +119,7 @@ fn test_get_all_stocks() {
         in_auction: None,
         in_sale: None,
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -145,7 +149,7 @@ fn test_get_all_stocks_with_start_after() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -162,6 +166,8 @@ fn test_get_all_stocks_with_start_after() {
         let ticker = format!("INFL{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         app.execute_contract(influencer.clone(), contract_addr.clone(), &create_msg, &[])
@@ -175,6 +181,7 @@ fn test_get_all_stocks_with_start_after() {
         in_auction: None,
         in_sale: None,
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -203,7 +210,7 @@ fn test_get_stocks_by_auction_status() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -223,6 +230,8 @@ fn test_get_stocks_by_auction_status() {
         let ticker = format!("INFL{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         let res = app
@@ -247,6 +256,17 @@ fn test_get_stocks_by_auction_status() {
     for i in 0..3 {
         let start_auction_msg = ExecuteMsg::StartAuction {
             stock_id: stock_ids[i],
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
         };
         app.execute_contract(
             influencers[i].clone(),
@@ -263,6 +283,7 @@ fn test_get_stocks_by_auction_status() {
         in_auction: Some(true),
         in_sale: None,
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -286,6 +307,7 @@ fn test_get_stocks_by_auction_status() {
         in_auction: Some(false),
         in_sale: None,
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -313,7 +335,7 @@ fn test_get_stocks_by_sale_status() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -333,6 +355,8 @@ fn test_get_stocks_by_sale_status() {
         let ticker = format!("INFL{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         let res = app
@@ -358,6 +382,17 @@ fn test_get_stocks_by_sale_status() {
         // Start auction
         let start_auction_msg = ExecuteMsg::StartAuction {
             stock_id: stock_ids[i],
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
         };
         app.execute_contract(
             influencers[i].clone(),
@@ -386,6 +421,7 @@ fn test_get_stocks_by_sale_status() {
         in_auction: None,
         in_sale: Some(true),
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -409,6 +445,7 @@ fn test_get_stocks_by_sale_status() {
         in_auction: None,
         in_sale: Some(false),
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -436,7 +473,7 @@ fn test_get_stocks_with_combined_filters() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -456,6 +493,8 @@ fn test_get_stocks_with_combined_filters() {
         let ticker = format!("INFL{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         let res = app
@@ -486,6 +525,17 @@ fn test_get_stocks_with_combined_filters() {
         // Start auction
         let start_auction_msg = ExecuteMsg::StartAuction {
             stock_id: stock_ids[i],
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
         };
         app.execute_contract(
             influencers[i].clone(),
@@ -512,6 +562,17 @@ fn test_get_stocks_with_combined_filters() {
     for i in 2..4 {
         let start_auction_msg = ExecuteMsg::StartAuction {
             stock_id: stock_ids[i],
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
         };
         app.execute_contract(
             influencers[i].clone(),
@@ -528,6 +589,7 @@ fn test_get_stocks_with_combined_filters() {
         in_auction: Some(true),
         in_sale: Some(false),
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -549,6 +611,7 @@ fn test_get_stocks_with_combined_filters() {
         in_auction: Some(false),
         in_sale: Some(true),
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -570,6 +633,7 @@ fn test_get_stocks_with_combined_filters() {
         in_auction: Some(false),
         in_sale: Some(false),
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -590,6 +654,7 @@ fn test_get_stocks_with_combined_filters() {
         in_auction: Some(true),
         in_sale: Some(false),
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -601,3 +666,81 @@ fn test_get_stocks_with_combined_filters() {
     assert_eq!(response.stocks.len(), 1);
     assert_eq!(response.stocks[0].id, stock_ids[2]);
 }
+
+#[test]
+fn test_unsettled_and_delisted_stocks_are_not_in_sale() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, ..) = setup_stock_in_sale(&mut app, &vault);
+
+    // A second stock whose auction is over but hasn't been settled
+    let influencer = app.api().addr_make("influencer2");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "OTHER".to_owned(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: stock_id + 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * 60 * 60 + 1);
+    });
+
+    let in_sale = |app: &cw_multi_test::App, in_sale: bool| -> Vec<u64> {
+        let response: GetStocksResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetAllStocks {
+                    start_after: None,
+                    in_auction: None,
+                    in_sale: Some(in_sale),
+                    marked_as_active_auction: None,
+                    scheduled: None,
+                },
+            )
+            .unwrap();
+
+        response.stocks.iter().map(|stock| stock.id).collect()
+    };
+
+    assert_eq!(in_sale(&app, true), vec![stock_id]);
+    assert_eq!(in_sale(&app, false), vec![stock_id + 1]);
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::DelistStock { stock_id },
+        &[],
+    )
+    .unwrap();
+
+    assert!(in_sale(&app, true).is_empty());
+    assert_eq!(in_sale(&app, false), vec![stock_id + 1, stock_id]);
+}
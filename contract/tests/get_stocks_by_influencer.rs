@@ -15,7 +15,7 @@ fn test_get_stocks_by_influencer() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -29,6 +29,8 @@ fn test_get_stocks_by_influencer() {
         let ticker = format!("INF1_{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         app.execute_contract(influencer1.clone(), contract_addr.clone(), &create_msg, &[])
@@ -42,6 +44,8 @@ fn test_get_stocks_by_influencer() {
         let ticker = format!("INF2_{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         app.execute_contract(influencer2.clone(), contract_addr.clone(), &create_msg, &[])
@@ -99,6 +103,7 @@ fn test_get_stocks_by_influencer() {
         in_sale: None,
         in_auction: None,
         marked_as_active_auction: None,
+        scheduled: None,
     };
 
     let response: GetStocksResponse = app
@@ -120,7 +125,7 @@ fn test_get_stocks_by_influencer_empty() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -153,7 +158,7 @@ fn test_get_stocks_by_influencer_with_start_after() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -169,6 +174,8 @@ fn test_get_stocks_by_influencer_with_start_after() {
         let ticker = format!("INF1_{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         let res = app
@@ -196,6 +203,8 @@ fn test_get_stocks_by_influencer_with_start_after() {
         let ticker = format!("INF2_{}", i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         app.execute_contract(
@@ -214,6 +223,8 @@ fn test_get_stocks_by_influencer_with_start_after() {
         let ticker = format!("INF1_{}", stock_id_len + i);
         let create_msg = ExecuteMsg::CreateStock {
             ticker: ticker.clone(),
+            total_shares: None,
+            metadata: None,
         };
 
         let res = app
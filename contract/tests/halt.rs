@@ -0,0 +1,220 @@
+use common::{setup_app, setup_stock_in_sale};
+use cosmwasm_std::coins;
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetHaltStatusResponse, QueryMsg},
+};
+
+mod common;
+
+#[test]
+fn test_stock_halt_blocks_new_orders_but_not_cancels() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, bidder1, _) = setup_stock_in_sale(&mut app, &vault);
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 10_000,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Only operators can halt
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Halt {
+            stock_id: Some(stock_id),
+            reason: "Investigating".to_owned(),
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    let res = app
+        .execute_contract(
+            vault.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Halt {
+                stock_id: Some(stock_id),
+                reason: "Investigating".to_owned(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let event = res.events.iter().find(|e| e.ty == "wasm-halt").unwrap();
+    assert!(event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "stock_id" && attr.value == stock_id.to_string()));
+
+    let res: GetHaltStatusResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetHaltStatus {
+                stock_id: Some(stock_id),
+            },
+        )
+        .unwrap();
+
+    assert!(res.is_halted);
+    assert!(res.global.is_none());
+    assert_eq!(res.stock.unwrap().reason, "Investigating");
+
+    // New orders are rejected
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(200_000, DENOM))
+        .unwrap();
+
+    let err = app
+        .execute_contract(
+            buyer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateBuyOrder {
+                stock_id,
+                price_per_share: 20,
+                shares: 10_000,
+            },
+            &coins(200_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("is halted"));
+
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::QuickSell {
+                stock_id,
+                shares: 1_000,
+                price_per_share: 10,
+                slippage: 0,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("is halted"));
+
+    // Cancels still go through
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelSellOrder { sell_order_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // Resuming lets trading continue
+    let res = app
+        .execute_contract(
+            vault.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Resume {
+                stock_id: Some(stock_id),
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert!(res.events.iter().any(|e| e.ty == "wasm-resume"));
+
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateBuyOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 10_000,
+        },
+        &coins(200_000, DENOM),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_global_halt_blocks_bids() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, bidder1, _) = setup_stock_in_sale(&mut app, &vault);
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Halt {
+            stock_id: None,
+            reason: "Upgrade".to_owned(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetHaltStatusResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetHaltStatus { stock_id: None },
+        )
+        .unwrap();
+
+    assert!(res.is_halted);
+    assert_eq!(res.global.unwrap().halted_by, vault);
+
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::PlaceBid {
+                stock_id,
+                price_per_share: 20,
+                shares: 100,
+            },
+            &coins(2_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Trading is halted"));
+
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateSellOrder {
+                stock_id,
+                price_per_share: 20,
+                shares: 100,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Trading is halted"));
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Resume { stock_id: None },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 100,
+        },
+        &[],
+    )
+    .unwrap();
+}
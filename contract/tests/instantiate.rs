@@ -16,7 +16,7 @@ fn test_instantiate() {
         .instantiate_contract(
             code_id,
             owner,
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
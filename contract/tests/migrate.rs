@@ -0,0 +1,754 @@
+use common::{contract_code, setup_app};
+use cosmwasm_std::{coins, Addr, Order};
+use cw_multi_test::{App, AppResponse, Executor};
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{
+        ExecuteMsg, GetBidByIdResponse, GetBidsResponse, GetBuyOrderEscrowResponse,
+        GetConfigResponse, GetOwnershipResponse, GetSellOrdersResponse, GetShareResponse,
+        GetSharesResponse, GetStockByIdResponse, GetStockByTickerResponse, InstantiateMsg,
+        MigrateMsg, OrderSort, QueryMsg, UpdateConfigMsg,
+    },
+    state::{BuyOrder, SellOrder, Share, BUY_ORDERS, SELL_ORDERS, SELL_ORDER_COUNT},
+};
+
+mod common;
+
+const CONTRACT_NAME: &str = "crates.io:influencer-stocks";
+
+// The records whose layout changed since 0.1.0, as 0.1.0 stored them
+mod v0_1_0 {
+    use cosmwasm_std::Addr;
+    use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone)]
+    pub struct State {
+        pub owner: Addr,
+    }
+
+    pub const STATE: Item<State> = Item::new("state");
+
+    #[derive(Serialize, Deserialize, Clone)]
+    pub struct Stock {
+        pub id: u64,
+        pub ticker: String,
+        pub influencer: Addr,
+        pub total_shares: u64,
+        pub auction_start: Option<u64>,
+        pub auction_end: Option<u64>,
+        pub marked_as_active_auction: bool,
+        pub created_at: u64,
+    }
+
+    pub struct StockIndexes<'a> {
+        pub influencer: MultiIndex<'a, Addr, Stock, &'a [u8]>,
+    }
+
+    impl IndexList<Stock> for StockIndexes<'_> {
+        fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Stock>> + '_> {
+            let v = vec![&self.influencer as &dyn Index<Stock>];
+            Box::new(v.into_iter())
+        }
+    }
+
+    pub const STOCKS: IndexedMap<&[u8], Stock, StockIndexes> = IndexedMap::new(
+        "stocks",
+        StockIndexes {
+            influencer: MultiIndex::new(
+                |_pk, stock| stock.influencer.clone(),
+                "stocks",
+                "stocks__influencer",
+            ),
+        },
+    );
+    pub const STOCK_COUNT: Item<u64> = Item::new("stock_count");
+
+    #[derive(Serialize, Deserialize, Clone)]
+    pub struct Bid {
+        pub id: u64,
+        pub stock_id: u64,
+        pub bidder: Addr,
+        pub price_per_share: u128,
+        pub shares_requested: u64,
+        pub remaining_shares: u64,
+        pub created_at: u64,
+        pub open: u8,
+        pub active: bool,
+    }
+
+    pub struct BidIndexes<'a> {
+        pub stock_id: MultiIndex<'a, u64, Bid, &'a [u8]>,
+        pub bidder: MultiIndex<'a, Addr, Bid, &'a [u8]>,
+        pub stock_open: MultiIndex<'a, (u64, u8), Bid, &'a [u8]>,
+    }
+
+    impl IndexList<Bid> for BidIndexes<'_> {
+        fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Bid>> + '_> {
+            let v = vec![
+                &self.stock_id as &dyn Index<Bid>,
+                &self.bidder as &dyn Index<Bid>,
+                &self.stock_open as &dyn Index<Bid>,
+            ];
+            Box::new(v.into_iter())
+        }
+    }
+
+    pub const BIDS: IndexedMap<&[u8], Bid, BidIndexes> = IndexedMap::new(
+        "bids",
+        BidIndexes {
+            stock_id: MultiIndex::new(|_pk, bid| bid.stock_id, "bids", "bids__stock_id"),
+            bidder: MultiIndex::new(|_pk, bid| bid.bidder.clone(), "bids", "bids__bidder"),
+            stock_open: MultiIndex::new(
+                |_pk, bid| (bid.stock_id, bid.open),
+                "bids",
+                "bids__stock_open",
+            ),
+        },
+    );
+    pub const BID_COUNT: Item<u64> = Item::new("bid_count");
+
+    #[derive(Serialize, Deserialize, Clone)]
+    pub struct Share {
+        pub id: u64,
+        pub stock_id: u64,
+        pub no_of_shares: u64,
+        pub owner: Addr,
+    }
+
+    pub struct ShareIndexes<'a> {
+        pub stock_id: MultiIndex<'a, u64, Share, &'a [u8]>,
+        pub owner: MultiIndex<'a, Addr, Share, &'a [u8]>,
+    }
+
+    impl IndexList<Share> for ShareIndexes<'_> {
+        fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Share>> + '_> {
+            let v = vec![
+                &self.stock_id as &dyn Index<Share>,
+                &self.owner as &dyn Index<Share>,
+            ];
+            Box::new(v.into_iter())
+        }
+    }
+
+    pub const SHARES: IndexedMap<&[u8], Share, ShareIndexes> = IndexedMap::new(
+        "share",
+        ShareIndexes {
+            stock_id: MultiIndex::new(|_pk, share| share.stock_id, "share", "share__stock_id"),
+            owner: MultiIndex::new(|_pk, share| share.owner.clone(), "share", "share__owner"),
+        },
+    );
+    pub const SHARE_COUNT: Item<u64> = Item::new("share_count");
+}
+
+// Mark the contract's state as written by another release
+fn set_stored_version(app: &mut App, contract_addr: &Addr, contract: &str, version: &str) {
+    let mut storage = app.contract_storage_mut(contract_addr);
+    cw2::set_contract_version(storage.as_mut(), contract, version).unwrap();
+}
+
+// Instantiate the contract and swap its state for what 0.1.0 left after instantiating,
+// the owner and the contract version. Tests then write the 0.1.0 records they need.
+fn instantiate_v0_1_0(app: &mut App, vault: &Addr) -> Addr {
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg::default(),
+            &[],
+            "Influencer Stocks",
+            Some(vault.to_string()),
+        )
+        .unwrap();
+
+    {
+        let mut storage = app.contract_storage_mut(&contract_addr);
+
+        let keys = storage
+            .range(None, None, Order::Ascending)
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+
+        for key in keys {
+            storage.remove(&key);
+        }
+
+        let state = v0_1_0::State {
+            owner: vault.clone(),
+        };
+
+        v0_1_0::STATE.save(storage.as_mut(), &state).unwrap();
+    }
+
+    set_stored_version(app, &contract_addr, CONTRACT_NAME, "0.1.0");
+
+    contract_addr
+}
+
+// A stock whose auction ran and was ended under 0.1.0
+fn settled_stock(id: u64, ticker: &str, influencer: &Addr) -> v0_1_0::Stock {
+    v0_1_0::Stock {
+        id,
+        ticker: ticker.to_owned(),
+        influencer: influencer.clone(),
+        total_shares: 1_000_000,
+        auction_start: Some(1_000),
+        auction_end: Some(2_000),
+        marked_as_active_auction: false,
+        created_at: 1_000,
+    }
+}
+
+fn migrate(app: &mut App, vault: &Addr, contract_addr: &Addr) -> AppResponse {
+    let new_code_id = app.store_code(contract_code());
+
+    app.migrate_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &MigrateMsg {},
+        new_code_id,
+    )
+    .unwrap()
+}
+
+fn has_attr(res: &AppResponse, key: &str, value: &str) -> bool {
+    res.custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == key && attr.value == value)
+}
+
+#[test]
+fn test_migrate_from_v0_1_0() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate_v0_1_0(&mut app, &vault);
+
+    let influencer = app.api().addr_make("influencer");
+    let holder = app.api().addr_make("holder");
+    let other = app.api().addr_make("other");
+
+    // Stock 1 was auctioned and settled, holder won two bids and other one.
+    // Stocks 2 and 3 have never been auctioned, and 3 reused stock 1's ticker.
+    {
+        let mut storage = app.contract_storage_mut(&contract_addr);
+
+        for stock in [
+            settled_stock(1, "TEST", &influencer),
+            v0_1_0::Stock {
+                auction_start: None,
+                auction_end: None,
+                ..settled_stock(2, "OTHER", &influencer)
+            },
+            v0_1_0::Stock {
+                auction_start: None,
+                auction_end: None,
+                ..settled_stock(3, " test", &influencer)
+            },
+        ] {
+            v0_1_0::STOCKS
+                .save(storage.as_mut(), &stock.id.to_be_bytes(), &stock)
+                .unwrap();
+        }
+
+        v0_1_0::STOCK_COUNT.save(storage.as_mut(), &3).unwrap();
+
+        let bids = [
+            (1, influencer.clone(), 0, 1_000_000, 0, 0),
+            (2, holder.clone(), 5, 300_000, 300_000, 1),
+            (3, other.clone(), 6, 400_000, 400_000, 1),
+            (4, holder.clone(), 7, 300_000, 300_000, 1),
+        ];
+
+        for (id, bidder, price_per_share, shares_requested, remaining_shares, open) in bids {
+            let bid = v0_1_0::Bid {
+                id,
+                stock_id: 1,
+                bidder,
+                price_per_share,
+                shares_requested,
+                remaining_shares,
+                created_at: 1_000 + id,
+                open,
+                active: false,
+            };
+
+            v0_1_0::BIDS
+                .save(storage.as_mut(), &id.to_be_bytes(), &bid)
+                .unwrap();
+        }
+
+        v0_1_0::BID_COUNT.save(storage.as_mut(), &4).unwrap();
+
+        // One share record per winning bid
+        let shares = [
+            (1, holder.clone(), 300_000),
+            (2, other.clone(), 400_000),
+            (3, holder.clone(), 300_000),
+        ];
+
+        for (id, owner, no_of_shares) in shares {
+            let share = v0_1_0::Share {
+                id,
+                stock_id: 1,
+                no_of_shares,
+                owner,
+            };
+
+            v0_1_0::SHARES
+                .save(storage.as_mut(), &id.to_be_bytes(), &share)
+                .unwrap();
+        }
+
+        v0_1_0::SHARE_COUNT.save(storage.as_mut(), &3).unwrap();
+
+        // holder lists part of their shares, the cheaper order has been filled already.
+        // other bids for more with funds the contract holds.
+        for (id, price_per_share, resolved_at) in [(1, 30, None), (2, 20, Some(3_000))] {
+            let order = SellOrder {
+                id,
+                stock_id: 1,
+                available_shares: 100_000,
+                price_per_share,
+                sold_shares: 0,
+                owner: holder.clone(),
+                created_at: 3_000,
+                resolved_at,
+            };
+
+            SELL_ORDERS
+                .save(storage.as_mut(), &id.to_be_bytes(), &order)
+                .unwrap();
+        }
+
+        SELL_ORDER_COUNT.save(storage.as_mut(), &2).unwrap();
+
+        let order = BuyOrder {
+            id: 1,
+            stock_id: 1,
+            requested_shares: 100_000,
+            price_per_share: 10,
+            bought_shares: 40_000,
+            owner: other.clone(),
+            created_at: 3_000,
+            resolved_at: None,
+        };
+
+        BUY_ORDERS
+            .save(storage.as_mut(), &1u64.to_be_bytes(), &order)
+            .unwrap();
+    }
+
+    app.send_tokens(vault.clone(), contract_addr.clone(), &coins(600_000, DENOM))
+        .unwrap();
+
+    let res = migrate(&mut app, &vault, &contract_addr);
+
+    assert!(has_attr(&res, "from_version", "0.1.0"));
+    assert!(has_attr(&res, "merged_share_records", "3"));
+    assert!(has_attr(&res, "locked_sell_orders", "1"));
+    assert!(has_attr(&res, "capped_sell_orders", "0"));
+    assert!(has_attr(&res, "indexed_open_orders", "2"));
+    assert!(has_attr(&res, "backfilled_escrows", "1"));
+    assert!(has_attr(&res, "converted_bids", "4"));
+    assert!(has_attr(&res, "indexed_tickers", "2"));
+
+    // Stocks read with the fields added since
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.ticker, "TEST");
+    assert_eq!(res.stock.influencer, influencer);
+    assert!(!res.stock.marked_as_active_auction);
+
+    // Tickers are registered, a duplicate stays with the oldest stock
+    let res: GetStockByTickerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockByTicker {
+                ticker: "test".to_owned(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.id, 1);
+
+    let res: GetStockByTickerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockByTicker {
+                ticker: "OTHER".to_owned(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.id, 2);
+
+    // Bids are open as a bool
+    let res: GetBidByIdResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetBidById { bid_id: 1 })
+        .unwrap();
+
+    assert!(!res.bid.open);
+
+    let res: GetBidByIdResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetBidById { bid_id: 2 })
+        .unwrap();
+
+    assert!(res.bid.open);
+    assert_eq!(res.bid.bidder, holder);
+
+    // and the open ones are indexed by price
+    let res: GetBidsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetOpenBidsByStock { stock_id: 1 },
+        )
+        .unwrap();
+
+    let prices = res
+        .bids
+        .iter()
+        .map(|bid| bid.price_per_share)
+        .collect::<Vec<_>>();
+
+    assert_eq!(prices, vec![5, 6, 7]);
+
+    // holder's two records are one position, with the listed shares locked
+    let res: GetSharesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSharesByStock { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.shares.len(), 2);
+
+    let res: GetShareResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetShare {
+                stock_id: 1,
+                owner: holder.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.share.no_of_shares, 600_000);
+    assert_eq!(res.share.locked_shares, 100_000);
+
+    // The legacy share records are gone
+    {
+        let storage = app.contract_storage(&contract_addr);
+
+        assert!(v0_1_0::SHARES
+            .may_load(storage.as_ref(), &1u64.to_be_bytes())
+            .unwrap()
+            .is_none());
+        assert!(v0_1_0::SHARE_COUNT
+            .may_load(storage.as_ref())
+            .unwrap()
+            .is_none());
+    }
+
+    // Only the open orders are in the order book
+    let res: GetSellOrdersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetOpenSellOrdersByStock {
+                stock_id: 1,
+                sort_by: OrderSort::PriceAsc,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        res.orders.iter().map(|order| order.id).collect::<Vec<_>>(),
+        vec![1]
+    );
+
+    let res: GetBuyOrderEscrowResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetBuyOrderEscrow { buy_order_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.amount, (60_000 * 10).to_string());
+
+    // The 0.1.0 owner still owns the contract
+    let res: GetOwnershipResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOwnership {})
+        .unwrap();
+
+    assert_eq!(res.owner, Some(vault.clone()));
+    assert_eq!(res.pending_owner, None);
+
+    // The contract runs on the values 0.1.0 had hardcoded, and the owner can change them
+    let res: GetConfigResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {})
+        .unwrap();
+
+    assert_eq!(res.config.denom, DENOM);
+    assert_eq!(res.config.total_shares, 1_000_000);
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+            total_shares: Some(2_000_000),
+            max_total_shares: Some(2_000_000),
+            ..UpdateConfigMsg::default()
+        }),
+        &[],
+    )
+    .unwrap();
+
+    // Running it again at the same version is a no-op
+    let res = migrate(&mut app, &vault, &contract_addr);
+
+    assert!(!res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "converted_bids"));
+}
+
+#[test]
+fn test_migrate_locks_shares_of_open_sell_orders() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate_v0_1_0(&mut app, &vault);
+
+    let influencer = app.api().addr_make("influencer");
+    let seller = app.api().addr_make("seller");
+    let buyer = app.api().addr_make("buyer");
+
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(1_000_000, DENOM))
+        .unwrap();
+
+    // seller won 1_000 shares over two bids. 0.1.0 checked each order against those
+    // on its own and locked nothing, so together the orders list 1_400 shares.
+    {
+        let mut storage = app.contract_storage_mut(&contract_addr);
+
+        let stock = settled_stock(1, "TEST", &influencer);
+
+        v0_1_0::STOCKS
+            .save(storage.as_mut(), &1u64.to_be_bytes(), &stock)
+            .unwrap();
+        v0_1_0::STOCK_COUNT.save(storage.as_mut(), &1).unwrap();
+
+        for (id, no_of_shares) in [(1, 600), (2, 400)] {
+            let share = v0_1_0::Share {
+                id,
+                stock_id: 1,
+                no_of_shares,
+                owner: seller.clone(),
+            };
+
+            v0_1_0::SHARES
+                .save(storage.as_mut(), &id.to_be_bytes(), &share)
+                .unwrap();
+        }
+
+        v0_1_0::SHARE_COUNT.save(storage.as_mut(), &2).unwrap();
+
+        for (id, available_shares, sold_shares, price_per_share) in
+            [(1, 600, 100, 20), (2, 700, 0, 25), (3, 200, 0, 30)]
+        {
+            let order = SellOrder {
+                id,
+                stock_id: 1,
+                available_shares,
+                price_per_share,
+                sold_shares,
+                owner: seller.clone(),
+                created_at: 3_000,
+                resolved_at: None,
+            };
+
+            SELL_ORDERS
+                .save(storage.as_mut(), &id.to_be_bytes(), &order)
+                .unwrap();
+        }
+
+        SELL_ORDER_COUNT.save(storage.as_mut(), &3).unwrap();
+    }
+
+    let res = migrate(&mut app, &vault, &contract_addr);
+
+    assert!(has_attr(&res, "locked_sell_orders", "2"));
+    assert!(has_attr(&res, "capped_sell_orders", "2"));
+    assert!(has_attr(&res, "indexed_open_orders", "2"));
+
+    let get_share = |app: &App| -> Share {
+        app.wrap()
+            .query_wasm_smart::<GetShareResponse>(
+                contract_addr.clone(),
+                &QueryMsg::GetShare {
+                    stock_id: 1,
+                    owner: seller.clone(),
+                },
+            )
+            .unwrap()
+            .share
+    };
+
+    assert_eq!(get_share(&app).locked_shares, 1_000);
+
+    // Order 2 is cut down to the 500 shares left after order 1, order 3 is cancelled
+    let res: GetSellOrdersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetOpenSellOrdersByStock {
+                stock_id: 1,
+                sort_by: OrderSort::PriceAsc,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        res.orders
+            .iter()
+            .map(|order| (order.id, order.available_shares - order.sold_shares))
+            .collect::<Vec<_>>(),
+        vec![(1, 500), (2, 500)]
+    );
+
+    let res = app
+        .execute_contract(
+            seller.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CancelSellOrder { sell_order_id: 2 },
+            &[],
+        )
+        .unwrap();
+
+    assert!(res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "unlocked_shares" && attr.value == "500"));
+
+    // Order 1 fills out of the locked shares
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateBuyOrder {
+            stock_id: 1,
+            price_per_share: 20,
+            shares: 500,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+
+    let share = get_share(&app);
+    assert_eq!((share.no_of_shares, share.locked_shares), (500, 0));
+}
+
+#[test]
+fn test_migrate_rejects_downgrade_and_other_contracts() {
+    let (mut app, vault) = setup_app();
+
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg::default(),
+            &[],
+            "Influencer Stocks",
+            Some(vault.to_string()),
+        )
+        .unwrap();
+
+    let new_code_id = app.store_code(contract_code());
+
+    set_stored_version(&mut app, &contract_addr, CONTRACT_NAME, "99.0.0");
+
+    let err = app
+        .migrate_contract(
+            vault.clone(),
+            contract_addr.clone(),
+            &MigrateMsg {},
+            new_code_id,
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("older version"));
+
+    set_stored_version(
+        &mut app,
+        &contract_addr,
+        "crates.io:other-contract",
+        "0.1.0",
+    );
+
+    let err = app
+        .migrate_contract(
+            vault.clone(),
+            contract_addr.clone(),
+            &MigrateMsg {},
+            new_code_id,
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Cannot migrate from crates.io:other-contract"));
+}
+
+#[test]
+fn test_migrate_refuses_running_auctions() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate_v0_1_0(&mut app, &vault);
+
+    let influencer = app.api().addr_make("influencer");
+
+    // Its bids paid the influencer as they came in, there is no escrow to refund them from
+    {
+        let mut storage = app.contract_storage_mut(&contract_addr);
+
+        let stock = v0_1_0::Stock {
+            marked_as_active_auction: true,
+            ..settled_stock(1, "TEST", &influencer)
+        };
+
+        v0_1_0::STOCKS
+            .save(storage.as_mut(), &stock.id.to_be_bytes(), &stock)
+            .unwrap();
+        v0_1_0::STOCK_COUNT.save(storage.as_mut(), &1).unwrap();
+    }
+
+    let new_code_id = app.store_code(contract_code());
+
+    let err = app
+        .migrate_contract(
+            vault.clone(),
+            contract_addr.clone(),
+            &MigrateMsg {},
+            new_code_id,
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("End the auction of stock 1 before migrating"));
+}
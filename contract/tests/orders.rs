@@ -1,11 +1,11 @@
-use common::{contract_code, setup_app};
+use common::{contract_code, setup_app, setup_stock_in_sale};
 use cosmwasm_std::coins;
 use cw_multi_test::Executor;
 use influencer_stocks::{
     contract::DENOM,
     msg::{
-        ExecuteMsg, GetBuyOrdersResponse, GetSalesResponse, GetSellOrdersResponse,
-        GetSharesResponse, InstantiateMsg, OrderSort, QueryMsg,
+        ExecuteMsg, GetBuyOrdersResponse, GetBuyPriceResponse, GetSalesResponse,
+        GetSellOrdersResponse, GetSharesResponse, InstantiateMsg, OrderSort, QueryMsg,
     },
 };
 
@@ -21,7 +21,7 @@ fn test_create_multiple_sell_multiple_buy_orders() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -33,6 +33,8 @@ fn test_create_multiple_sell_multiple_buy_orders() {
 
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_owned(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -51,7 +53,20 @@ fn test_create_multiple_sell_multiple_buy_orders() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -446,3 +461,112 @@ fn test_create_multiple_sell_multiple_buy_orders() {
     assert_eq!(orders[1].price_per_share, 5);
     assert_eq!(orders[1].bought_shares, 0);
 }
+
+#[test]
+fn test_order_book_price_time_priority() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, bidder1, bidder2) = setup_stock_in_sale(&mut app, &vault);
+
+    // Two asks at the same price, an older one from bidder1 and a newer one from bidder2,
+    // plus a cheaper ask that is cancelled before anyone buys
+    for (seller, price_per_share, shares) in [
+        (bidder1.clone(), 20u128, 1_000u64),
+        (bidder2.clone(), 20, 1_000),
+        (bidder2.clone(), 15, 1_000),
+    ] {
+        app.execute_contract(
+            seller,
+            contract_addr.clone(),
+            &ExecuteMsg::CreateSellOrder {
+                stock_id,
+                price_per_share,
+                shares,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    app.execute_contract(
+        bidder2.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelSellOrder { sell_order_id: 3 },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetSellOrdersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetOpenSellOrdersByStock {
+                stock_id,
+                sort_by: OrderSort::PriceAsc,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        res.orders.iter().map(|order| order.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    let res: GetBuyPriceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetBuyPrice {
+                stock_id,
+                requested_shares: 1_500,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.total_price, (1_500 * 20).to_string());
+
+    // Buying 1_500 shares fills the oldest order first
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(30_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateBuyOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 1_500,
+        },
+        &coins(30_000, DENOM),
+    )
+    .unwrap();
+
+    let res: GetSellOrdersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetOpenSellOrdersByStock {
+                stock_id,
+                sort_by: OrderSort::PriceAsc,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.orders.len(), 1);
+    assert_eq!(res.orders[0].id, 2);
+    assert_eq!(res.orders[0].sold_shares, 500);
+
+    // Asking for more than the book holds reports the whole volume
+    let err = app
+        .wrap()
+        .query_wasm_smart::<GetBuyPriceResponse>(
+            contract_addr.clone(),
+            &QueryMsg::GetBuyPrice {
+                stock_id,
+                requested_shares: 1_000,
+            },
+        )
+        .unwrap_err();
+
+    assert!(err.to_string().contains("Only 500 shares available"));
+}
@@ -0,0 +1,260 @@
+use common::{instantiate, setup_app};
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    msg::{
+        ExecuteMsg, GetOwnershipResponse, GetRoleMembersResponse, InstantiateMsg, QueryMsg,
+        UpdateConfigMsg,
+    },
+    state::Role,
+};
+
+mod common;
+
+fn update_config_msg() -> ExecuteMsg {
+    ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        min_bid_increment: Some(10),
+        ..UpdateConfigMsg::default()
+    })
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate(&mut app, &vault, &InstantiateMsg::default());
+
+    let new_owner = app.api().addr_make("new_owner");
+    let random = app.api().addr_make("random");
+
+    // Only the owner can propose
+    let err = app
+        .execute_contract(
+            random.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::ProposeNewOwner {
+                new_owner: random.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::ProposeNewOwner {
+            new_owner: new_owner.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetOwnershipResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOwnership {})
+        .unwrap();
+
+    assert_eq!(res.owner, Some(vault.clone()));
+    assert_eq!(res.pending_owner, Some(new_owner.clone()));
+
+    // Only the proposed owner can accept
+    let err = app
+        .execute_contract(
+            random.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::AcceptOwnership {},
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    app.execute_contract(
+        new_owner.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::AcceptOwnership {},
+        &[],
+    )
+    .unwrap();
+
+    let res: GetOwnershipResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOwnership {})
+        .unwrap();
+
+    assert_eq!(res.owner, Some(new_owner.clone()));
+    assert_eq!(res.pending_owner, None);
+
+    // The previous owner lost its privileges
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &update_config_msg(),
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        new_owner.clone(),
+        contract_addr.clone(),
+        &update_config_msg(),
+        &[],
+    )
+    .unwrap();
+
+    // After renouncing nobody owns the contract
+    app.execute_contract(
+        new_owner.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::RenounceOwnership {},
+        &[],
+    )
+    .unwrap();
+
+    let res: GetOwnershipResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOwnership {})
+        .unwrap();
+
+    assert_eq!(res.owner, None);
+
+    app.execute_contract(
+        new_owner.clone(),
+        contract_addr.clone(),
+        &update_config_msg(),
+        &[],
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_operator_role_can_end_auction() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate(&mut app, &vault, &InstantiateMsg::default());
+
+    let influencer = app.api().addr_make("influencer");
+    let operator = app.api().addr_make("operator");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "TEST".to_owned(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Not an operator yet
+    let err = app
+        .execute_contract(
+            operator.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::EndAuction { stock_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    // Only the owner grants roles
+    app.execute_contract(
+        operator.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::GrantRole {
+            role: Role::Operator,
+            address: operator.to_string(),
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::GrantRole {
+            role: Role::Operator,
+            address: operator.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetRoleMembersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetRoleMembers {
+                role: Role::Operator,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.members, vec![operator.clone()]);
+
+    let res: GetRoleMembersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetRoleMembers {
+                role: Role::FeeManager,
+            },
+        )
+        .unwrap();
+
+    assert!(res.members.is_empty());
+
+    app.execute_contract(
+        operator.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::EndAuction { stock_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::RevokeRole {
+            role: Role::Operator,
+            address: operator.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetRoleMembersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetRoleMembers {
+                role: Role::Operator,
+            },
+        )
+        .unwrap();
+
+    assert!(res.members.is_empty());
+}
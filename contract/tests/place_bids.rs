@@ -1,11 +1,11 @@
 use common::{contract_code, setup_app};
 use cosmwasm_std::coins;
-use cw_multi_test::Executor;
+use cw_multi_test::{App, Executor};
 use influencer_stocks::{
     contract::{execute::stocks::TOTAL_SHARES, DENOM},
     msg::{
         ExecuteMsg, GetBidByIdResponse, GetBidsResponse, GetMinimumBidPriceResponse,
-        InstantiateMsg, QueryMsg,
+        GetStockByIdResponse, InstantiateMsg, QueryMsg,
     },
     state::Bid,
 };
@@ -23,7 +23,7 @@ fn test_place_bid_success() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -38,6 +38,8 @@ fn test_place_bid_success() {
     // Create a stock
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -54,7 +56,20 @@ fn test_place_bid_success() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -72,6 +87,7 @@ fn test_place_bid_success() {
     let query_bid_msg = QueryMsg::GetMinimumBidPrice {
         stock_id,
         shares_requested: shares,
+        bidder: None,
     };
 
     let res: GetMinimumBidPriceResponse = app
@@ -135,10 +151,10 @@ fn test_place_bid_success() {
     assert_eq!(bid_response.bid.stock_id, stock_id);
     assert_eq!(bid_response.bid.price_per_share, price_per_share);
     assert_eq!(bid_response.bid.shares_requested, shares);
-    assert_eq!(bid_response.bid.open, 1);
+    assert!(bid_response.bid.open);
     assert_eq!(bid_response.bid.active, true);
 
-    // Check contract's balance is 0 (all funds disbursed)
+    // Check the contract holds the bid in escrow until the auction ends
     let contract_balance = app
         .wrap()
         .query_balance(contract_addr.clone().to_string(), DENOM)
@@ -147,8 +163,8 @@ fn test_place_bid_success() {
         .u128();
 
     assert_eq!(
-        contract_balance, 0,
-        "Contract's balance should be 0 after transfers"
+        contract_balance, 1_000_000,
+        "Contract should hold the bid's funds"
     );
 
     // Verify that the bidders balance is now 0
@@ -161,7 +177,7 @@ fn test_place_bid_success() {
 
     assert_eq!(bidder_balance, 0);
 
-    // Verify that influencer's balance increased by 1_000_000
+    // Verify that influencer is not paid before the auction ends
     let influencer_balance = app
         .wrap()
         .query_balance(influencer.clone().to_string(), DENOM)
@@ -169,7 +185,7 @@ fn test_place_bid_success() {
         .amount
         .u128();
 
-    assert_eq!(influencer_balance - influencer_balance_before, 1_000_000);
+    assert_eq!(influencer_balance, influencer_balance_before);
 
     // Verify bid and share distribution
     let query_bid_msg = QueryMsg::GetOpenBidsByStock { stock_id };
@@ -191,7 +207,7 @@ fn test_place_bid_success() {
                     shares_requested: 1_000_000,
                     remaining_shares: 900_000,
                     created_at: res.bids[0].created_at,
-                    open: 1,
+                    open: true,
                     active: true
                 },
                 Bid {
@@ -202,7 +218,7 @@ fn test_place_bid_success() {
                     shares_requested: 100_000,
                     remaining_shares: 100_000,
                     created_at: res.bids[1].created_at,
-                    open: 1,
+                    open: true,
                     active: true
                 },
             ]
@@ -219,6 +235,7 @@ fn test_place_bid_success() {
     let query_bid_msg = QueryMsg::GetMinimumBidPrice {
         stock_id,
         shares_requested: shares,
+        bidder: None,
     };
 
     let res: GetMinimumBidPriceResponse = app
@@ -256,7 +273,7 @@ fn test_place_bid_success() {
     )
     .unwrap();
 
-    // Check contract's balance is 0 (all funds disbursed)
+    // Check the contract holds the winning bids in escrow, outbid funds were refunded
     let contract_balance = app
         .wrap()
         .query_balance(contract_addr.clone().to_string(), DENOM)
@@ -265,8 +282,9 @@ fn test_place_bid_success() {
         .u128();
 
     assert_eq!(
-        contract_balance, 0,
-        "Contract's balance should be 0 after transfers"
+        contract_balance,
+        1_000_000 - 500_000 + 950_000 * 11,
+        "Contract should hold the winning bids' funds"
     );
 
     // Verify that the bidder_2 balance is now 0
@@ -289,7 +307,7 @@ fn test_place_bid_success() {
 
     assert_eq!(bidder_1_balance, 500_000);
 
-    // Verify that influencer's balance increased by 950_000 * bid_price - refunded bids
+    // Verify that influencer is not paid before the auction ends
     let influencer_balance = app
         .wrap()
         .query_balance(influencer.clone().to_string(), DENOM)
@@ -297,7 +315,7 @@ fn test_place_bid_success() {
         .amount
         .u128();
 
-    assert_eq!(influencer_balance - influencer_balance_before, 9_950_000);
+    assert_eq!(influencer_balance, influencer_balance_before);
 
     // Verify bid and share distribution
     let query_bid_msg = QueryMsg::GetOpenBidsByStock { stock_id };
@@ -319,7 +337,7 @@ fn test_place_bid_success() {
                     shares_requested: 100_000,
                     remaining_shares: 50_000,
                     created_at: res.bids[0].created_at,
-                    open: 1,
+                    open: true,
                     active: true
                 },
                 Bid {
@@ -330,7 +348,7 @@ fn test_place_bid_success() {
                     shares_requested: 950_000,
                     remaining_shares: 950_000,
                     created_at: res.bids[1].created_at,
-                    open: 1,
+                    open: true,
                     active: true
                 },
             ]
@@ -345,7 +363,7 @@ fn test_place_bid_success() {
         .query_wasm_smart(contract_addr.clone(), &query_bid_msg)
         .unwrap();
 
-    assert_eq!(bid_response.bid.open, 0);
+    assert!(!bid_response.bid.open);
     assert_eq!(bid_response.bid.shares_requested, TOTAL_SHARES);
     assert_eq!(bid_response.bid.remaining_shares, 0);
     assert_eq!(bid_response.bid.price_per_share, 0);
@@ -364,7 +382,7 @@ fn test_auction_inactive() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Contract",
             None,
@@ -377,6 +395,8 @@ fn test_auction_inactive() {
     // Create stock but don't start auction
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -433,7 +453,7 @@ fn test_excess_fund_refund() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -447,6 +467,8 @@ fn test_excess_fund_refund() {
     // Create and start auction
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_string(),
+        total_shares: None,
+        metadata: None,
     };
     let res = app
         .execute_contract(influencer.clone(), contract_addr.clone(), &create_msg, &[])
@@ -464,7 +486,20 @@ fn test_excess_fund_refund() {
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
-        &ExecuteMsg::StartAuction { stock_id },
+        &ExecuteMsg::StartAuction {
+            stock_id,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
         &[],
     )
     .unwrap();
@@ -504,7 +539,7 @@ fn test_bid_on_nonexistent_stock() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -548,7 +583,7 @@ fn test_bid_on_expired_stock() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -561,6 +596,8 @@ fn test_bid_on_expired_stock() {
     // Create and start auction
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -579,7 +616,20 @@ fn test_bid_on_expired_stock() {
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
-        &ExecuteMsg::StartAuction { stock_id },
+        &ExecuteMsg::StartAuction {
+            stock_id,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
         &[],
     )
     .unwrap();
@@ -620,7 +670,7 @@ fn test_bid_below_minimum_price() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -634,6 +684,8 @@ fn test_bid_below_minimum_price() {
     // Create and start auction
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -652,7 +704,20 @@ fn test_bid_below_minimum_price() {
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
-        &ExecuteMsg::StartAuction { stock_id },
+        &ExecuteMsg::StartAuction {
+            stock_id,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
         &[],
     )
     .unwrap();
@@ -712,7 +777,7 @@ fn test_sent_funds_less_than_expected_amount() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -725,6 +790,8 @@ fn test_sent_funds_less_than_expected_amount() {
     // Create and start auction
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -743,7 +810,20 @@ fn test_sent_funds_less_than_expected_amount() {
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
-        &ExecuteMsg::StartAuction { stock_id },
+        &ExecuteMsg::StartAuction {
+            stock_id,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
         &[],
     )
     .unwrap();
@@ -778,3 +858,144 @@ fn test_sent_funds_less_than_expected_amount() {
         total_amount - 100
     )));
 }
+
+#[test]
+fn test_soft_close_extends_auction() {
+    let (mut app, vault) = setup_app();
+
+    const MINUTE: u64 = 60 * 1000;
+
+    // Bids in the last 10 minutes add 5 minutes, for at most 8 minutes in total
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg {
+                soft_close_window: Some(10 * MINUTE),
+                soft_close_extension: Some(5 * MINUTE),
+                max_auction_extension: Some(8 * MINUTE),
+                ..InstantiateMsg::default()
+            },
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer = app.api().addr_make("influencer");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "TEST".to_string(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    let auction_end = res.stock.auction_end.unwrap();
+
+    let bidder = app.api().addr_make("bidder");
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(1_000_000, DENOM))
+        .unwrap();
+
+    let mut price_per_share = 1;
+
+    // Move to `minutes_left` before the original end, bid and return the end attribute
+    let mut bid_at = |app: &mut App, minutes_left: u64| {
+        app.update_block(|block| {
+            let now = block.time.nanos() / 1_000_000;
+            block.time = block
+                .time
+                .plus_nanos((auction_end - minutes_left * MINUTE - now) * 1_000_000);
+        });
+
+        let res = app
+            .execute_contract(
+                bidder.clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::PlaceBid {
+                    stock_id: 1,
+                    price_per_share,
+                    shares: 1_000,
+                },
+                &coins(price_per_share * 1_000, DENOM),
+            )
+            .unwrap();
+
+        price_per_share += 1;
+
+        let attrs = res.custom_attrs(1);
+        let value = |key: &str| {
+            attrs
+                .iter()
+                .find(|attr| attr.key == key)
+                .unwrap()
+                .value
+                .clone()
+        };
+
+        (
+            value("auction_end").parse::<u64>().unwrap(),
+            value("auction_extended"),
+        )
+    };
+
+    // Outside the window nothing changes
+    assert_eq!(bid_at(&mut app, 20), (auction_end, "false".to_string()));
+
+    // Inside it the end moves out by 5 minutes
+    assert_eq!(
+        bid_at(&mut app, 5),
+        (auction_end + 5 * MINUTE, "true".to_string())
+    );
+
+    // But never past the 8 minute cap
+    assert_eq!(
+        bid_at(&mut app, 1),
+        (auction_end + 8 * MINUTE, "true".to_string())
+    );
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.auction_end, Some(auction_end + 8 * MINUTE));
+}
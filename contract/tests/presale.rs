@@ -0,0 +1,216 @@
+use common::{place_bid, setup_app, setup_stock, start_auction, AuctionParams};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{error::AnyResult, App, AppResponse, Executor};
+use influencer_stocks::{
+    contract::{
+        merkle::{hash_pair, presale_leaf},
+        DENOM,
+    },
+    msg::{ExecuteMsg, InstantiateMsg},
+    state::Presale,
+};
+
+mod common;
+
+const HOUR: u64 = 60 * 60;
+
+// Allow list of alice for 100_000 shares and bob for 50_000.
+// Returns the hex encoded root and the proofs of alice and bob.
+fn allow_list(alice: &Addr, bob: &Addr) -> (String, Vec<String>, Vec<String>) {
+    let alice_leaf = presale_leaf(alice, 100_000);
+    let bob_leaf = presale_leaf(bob, 50_000);
+    let filler_leaf = presale_leaf(&Addr::unchecked("filler"), 1);
+
+    let left = hash_pair(&alice_leaf, &bob_leaf);
+    let right = hash_pair(&filler_leaf, &filler_leaf);
+    let root = hash_pair(&left, &right);
+
+    (
+        hex::encode(root),
+        vec![hex::encode(bob_leaf), hex::encode(right)],
+        vec![hex::encode(alice_leaf), hex::encode(right)],
+    )
+}
+
+// Start an auction with a presale for the first hour
+fn setup_presale(app: &mut App, vault: &Addr, merkle_root: String) -> Addr {
+    let (contract_addr, influencer) = setup_stock(app, vault, &InstantiateMsg::default());
+
+    let public_start = app.block_info().time.nanos() / 1_000_000 + HOUR * 1000;
+
+    let res = start_auction(
+        app,
+        &contract_addr,
+        &influencer,
+        AuctionParams {
+            presale: Some(Presale {
+                merkle_root,
+                public_start,
+            }),
+            ..AuctionParams::default()
+        },
+    );
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "public_start" && attr.value == public_start.to_string()));
+
+    contract_addr
+}
+
+// Fund `bidder` from the vault and bid 1 per share for `shares` in the presale
+fn place_presale_bid(
+    app: &mut App,
+    vault: &Addr,
+    contract_addr: &Addr,
+    bidder: &Addr,
+    shares: u64,
+    allocation: u64,
+    proof: &[String],
+) -> AnyResult<AppResponse> {
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(shares as u128, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::PlacePresaleBid {
+            stock_id: 1,
+            price_per_share: 1,
+            shares,
+            allocation,
+            proof: proof.to_vec(),
+        },
+        &coins(shares as u128, DENOM),
+    )
+}
+
+#[test]
+fn test_presale_takes_allow_listed_bids_only() {
+    let (mut app, vault) = setup_app();
+
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+    let outsider = app.api().addr_make("outsider");
+
+    let (root, alice_proof, bob_proof) = allow_list(&alice, &bob);
+    let contract_addr = setup_presale(&mut app, &vault, root);
+
+    let res = place_presale_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &alice,
+        60_000,
+        100_000,
+        &alice_proof,
+    )
+    .unwrap();
+    assert!(res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "presale" && attr.value == "true"));
+
+    // The allocation counts every open bid of the address
+    let err = place_presale_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &alice,
+        50_000,
+        100_000,
+        &alice_proof,
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bidder can only bid for 40000 more shares in the presale"));
+
+    // Claiming a bigger allocation than was allow-listed
+    let err = place_presale_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bob,
+        80_000,
+        80_000,
+        &bob_proof,
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bidder is not on the presale allow list of stock 1"));
+
+    // Someone else's proof
+    let err = place_presale_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &outsider,
+        50_000,
+        50_000,
+        &bob_proof,
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bidder is not on the presale allow list of stock 1"));
+
+    let err = place_bid(&mut app, &vault, &contract_addr, &outsider, 1, 10_000).unwrap_err();
+    assert!(err.root_cause().to_string().contains("is in presale until"));
+
+    place_presale_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bob,
+        50_000,
+        50_000,
+        &bob_proof,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_public_phase_starts_at_public_start() {
+    let (mut app, vault) = setup_app();
+
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+    let outsider = app.api().addr_make("outsider");
+
+    let (root, alice_proof, _) = allow_list(&alice, &bob);
+    let contract_addr = setup_presale(&mut app, &vault, root);
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(HOUR);
+    });
+
+    // Anyone can bid once the public phase starts, presale bids are over
+    let res = place_bid(&mut app, &vault, &contract_addr, &outsider, 1, 500_000).unwrap();
+    assert!(res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "presale" && attr.value == "false"));
+
+    let err = place_presale_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &alice,
+        10_000,
+        100_000,
+        &alice_proof,
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Stock 1 is not in presale"));
+
+    place_bid(&mut app, &vault, &contract_addr, &alice, 1, 300_000).unwrap();
+}
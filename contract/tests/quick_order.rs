@@ -21,7 +21,7 @@ fn test_quick_buy() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -33,6 +33,8 @@ fn test_quick_buy() {
 
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_owned(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -51,7 +53,20 @@ fn test_quick_buy() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -300,7 +315,7 @@ fn test_quick_sell() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -312,6 +327,8 @@ fn test_quick_sell() {
 
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_owned(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -330,7 +347,20 @@ fn test_quick_sell() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -526,7 +556,7 @@ fn test_quick_buy_insufficient_funds() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -537,6 +567,8 @@ fn test_quick_buy_insufficient_funds() {
     let influencer = app.api().addr_make("influencer");
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_owned(),
+        total_shares: None,
+        metadata: None,
     };
     let res = app
         .execute_contract(influencer.clone(), contract_addr.clone(), &create_msg, &[])
@@ -555,7 +587,20 @@ fn test_quick_buy_insufficient_funds() {
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
-        &ExecuteMsg::StartAuction { stock_id },
+        &ExecuteMsg::StartAuction {
+            stock_id,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
         &[],
     )
     .unwrap();
@@ -632,7 +677,7 @@ fn test_quick_sell_slippage_exceeded() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -643,6 +688,8 @@ fn test_quick_sell_slippage_exceeded() {
     let influencer = app.api().addr_make("influencer");
     let create_msg = ExecuteMsg::CreateStock {
         ticker: "TEST".to_owned(),
+        total_shares: None,
+        metadata: None,
     };
     let res = app
         .execute_contract(influencer.clone(), contract_addr.clone(), &create_msg, &[])
@@ -661,7 +708,20 @@ fn test_quick_sell_slippage_exceeded() {
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
-        &ExecuteMsg::StartAuction { stock_id },
+        &ExecuteMsg::StartAuction {
+            stock_id,
+            start_at: None,
+            duration: None,
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
         &[],
     )
     .unwrap();
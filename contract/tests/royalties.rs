@@ -0,0 +1,207 @@
+use common::{setup_app, setup_stock_in_sale_with};
+use cosmwasm_std::coins;
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetRoyaltyResponse, GetSalesResponse, InstantiateMsg, QueryMsg},
+};
+
+mod common;
+
+#[test]
+fn test_set_royalty() {
+    let (mut app, vault) = setup_app();
+
+    // Royalties are capped at 5%
+    let (contract_addr, stock_id, influencer, bidder1, _) = setup_stock_in_sale_with(
+        &mut app,
+        &vault,
+        &InstantiateMsg {
+            max_royalty_bps: Some(500),
+            ..InstantiateMsg::default()
+        },
+    );
+
+    // No royalty until the influencer sets one
+    let res: GetRoyaltyResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetRoyalty { stock_id })
+        .unwrap();
+
+    assert_eq!(res.royalty_bps, 0);
+    assert_eq!(res.payout_address, influencer);
+
+    // Only the influencer sets the royalty
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::SetRoyalty {
+                stock_id,
+                royalty_bps: 300,
+                payout_address: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    // Above the cap is rejected
+    let err = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::SetRoyalty {
+                stock_id,
+                royalty_bps: 501,
+                payout_address: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Royalty cannot exceed 500 basis points"));
+
+    let payout = app.api().addr_make("payout");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::SetRoyalty {
+            stock_id,
+            royalty_bps: 300,
+            payout_address: Some(payout.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetRoyaltyResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetRoyalty { stock_id })
+        .unwrap();
+
+    assert_eq!(res.royalty_bps, 300);
+    assert_eq!(res.payout_address, payout);
+    assert_eq!(res.total_paid, "0");
+}
+
+#[test]
+fn test_secondary_sales_pay_royalty() {
+    let (mut app, vault) = setup_app();
+    let collector = app.api().addr_make("collector");
+
+    // 1% protocol fee, royalties capped at 5%
+    let (contract_addr, stock_id, influencer, bidder1, _) = setup_stock_in_sale_with(
+        &mut app,
+        &vault,
+        &InstantiateMsg {
+            fee_bps: Some(100),
+            fee_collector: Some(collector.to_string()),
+            max_royalty_bps: Some(500),
+            ..InstantiateMsg::default()
+        },
+    );
+
+    let payout = app.api().addr_make("payout");
+
+    // 2.5% royalty
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::SetRoyalty {
+            stock_id,
+            royalty_bps: 250,
+            payout_address: Some(payout.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let seller_balance_before = app
+        .wrap()
+        .query_balance(bidder1.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 10_000,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(200_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::QuickBuy {
+            stock_id,
+            shares: 10_000,
+            slippage: 0,
+        },
+        &coins(200_000, DENOM),
+    )
+    .unwrap();
+
+    // The seller pays both the fee and the royalty
+    let seller_balance = app
+        .wrap()
+        .query_balance(bidder1.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(
+        seller_balance - seller_balance_before,
+        200_000 - 2_000 - 5_000
+    );
+
+    let payout_balance = app
+        .wrap()
+        .query_balance(payout.to_string(), DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    assert_eq!(payout_balance, 5_000);
+
+    let res: GetSalesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSalesByStock { stock_id },
+        )
+        .unwrap();
+
+    let sale = res.sales.iter().find(|sale| sale.to == buyer).unwrap();
+    assert_eq!(sale.fee, 2_000);
+    assert_eq!(sale.royalty, 5_000);
+
+    // Auction sales don't pay a royalty
+    assert!(res
+        .sales
+        .iter()
+        .filter(|sale| sale.from == influencer)
+        .all(|sale| sale.royalty == 0));
+
+    let res: GetRoyaltyResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetRoyalty { stock_id })
+        .unwrap();
+
+    assert_eq!(res.total_paid, "5000");
+}
@@ -0,0 +1,524 @@
+use common::{balance, get_shares, setup_app, setup_auction, AuctionParams};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use influencer_stocks::{
+    contract::{execute::sealed::sealed_bid_commitment, DENOM},
+    msg::{ExecuteMsg, GetSealedBidResponse, InstantiateMsg, QueryMsg},
+    state::SealedBidAuction,
+};
+
+mod common;
+
+const HOUR: u64 = 60 * 60;
+
+// Start a sealed bid auction with an hour to reveal after the day long commit phase
+fn setup_sealed_auction(
+    app: &mut App,
+    vault: &Addr,
+    msg: &InstantiateMsg,
+    uniform_price: Option<bool>,
+) -> (Addr, Addr) {
+    setup_auction(
+        app,
+        vault,
+        msg,
+        AuctionParams {
+            uniform_price,
+            sealed_bid: Some(SealedBidAuction {
+                reveal_duration: HOUR * 1000,
+            }),
+            ..AuctionParams::default()
+        },
+    )
+}
+
+// Commit to a (price_per_share, shares, salt) bid, returning its id
+fn commit_bid(
+    app: &mut App,
+    vault: &Addr,
+    contract_addr: &Addr,
+    bidder: &Addr,
+    (price_per_share, shares, salt): (u128, u64, &str),
+    deposit: u128,
+) -> u64 {
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(deposit, DENOM))
+        .unwrap();
+
+    let res = app
+        .execute_contract(
+            bidder.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CommitBid {
+                stock_id: 1,
+                commitment: sealed_bid_commitment(bidder, price_per_share, shares, salt),
+            },
+            &coins(deposit, DENOM),
+        )
+        .unwrap();
+
+    res.custom_attrs(1)
+        .iter()
+        .find(|attr| attr.key == "bid_id")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap()
+}
+
+fn reveal_bid(
+    app: &mut App,
+    contract_addr: &Addr,
+    bidder: &Addr,
+    bid_id: u64,
+    price_per_share: u128,
+    shares: u64,
+    salt: &str,
+) -> String {
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::RevealBid {
+            bid_id,
+            price_per_share,
+            shares,
+            salt: salt.to_string(),
+        },
+        &[],
+    )
+    .map(|_| String::new())
+    .unwrap_or_else(|err| err.root_cause().to_string())
+}
+
+#[test]
+fn test_sealed_bids_are_ranked_at_settlement() {
+    let (mut app, vault) = setup_app();
+
+    // Half of an unrevealed deposit is kept
+    let (contract_addr, influencer) = setup_sealed_auction(
+        &mut app,
+        &vault,
+        &InstantiateMsg {
+            unrevealed_slash_bps: Some(5_000),
+            ..InstantiateMsg::default()
+        },
+        None,
+    );
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+    let bidder3 = app.api().addr_make("bidder3");
+
+    // bidder1 sends more than the bid needs
+    let bid1 = commit_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bidder1,
+        (10, 600_000, "a"),
+        7_000_000,
+    );
+    let bid2 = commit_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bidder2,
+        (20, 600_000, "b"),
+        12_000_000,
+    );
+    let bid3 = commit_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bidder3,
+        (30, 1_000, "c"),
+        30_000,
+    );
+
+    let outsider = app.api().addr_make("outsider");
+    app.send_tokens(vault.clone(), outsider.clone(), &coins(100_000, DENOM))
+        .unwrap();
+
+    // Open bids aren't taken
+    let err = app
+        .execute_contract(
+            outsider.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::PlaceBid {
+                stock_id: 1,
+                price_per_share: 10,
+                shares: 1_000,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Stock 1 takes sealed bids, commit one instead"));
+
+    // Nothing is revealed before the commit phase is over
+    let err = reveal_bid(&mut app, &contract_addr, &bidder1, bid1, 10, 600_000, "a");
+    assert!(err.contains("Reveal phase starts after"));
+
+    let res: GetSealedBidResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSealedBid { bid_id: bid1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.sealed_bid.deposit, 7_000_000);
+    assert!(!res.sealed_bid.revealed);
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * HOUR + 1);
+    });
+
+    let err = app
+        .execute_contract(
+            outsider.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CommitBid {
+                stock_id: 1,
+                commitment: sealed_bid_commitment(&outsider, 40, 1_000, "d"),
+            },
+            &coins(40_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Commit phase has ended"));
+
+    let err = reveal_bid(&mut app, &contract_addr, &bidder1, bid1, 10, 600_000, "b");
+    assert!(err.contains("Revealed bid doesn't match its commitment"));
+
+    // Only the bidder reveals their bid
+    let err = reveal_bid(&mut app, &contract_addr, &bidder2, bid1, 10, 600_000, "a");
+    assert!(err.contains("Unauthorized"));
+
+    assert_eq!(
+        reveal_bid(&mut app, &contract_addr, &bidder1, bid1, 10, 600_000, "a"),
+        ""
+    );
+    assert_eq!(
+        reveal_bid(&mut app, &contract_addr, &bidder2, bid2, 20, 600_000, "b"),
+        ""
+    );
+
+    // The auction can't be ended while bids can still be revealed
+    let err = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::EndAuction { stock_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Reveal phase ends at"));
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(HOUR);
+    });
+
+    let err = reveal_bid(&mut app, &contract_addr, &bidder3, bid3, 30, 1_000, "c");
+    assert!(err.contains("Reveal phase has ended"));
+
+    app.execute_contract(
+        bidder3.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::SettleAuction {
+            stock_id: 1,
+            limit: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // bidder2 outbids 200_000 of bidder1's shares
+    assert_eq!(get_shares(&app, &contract_addr, &bidder1), 400_000);
+    assert_eq!(get_shares(&app, &contract_addr, &bidder2), 600_000);
+
+    // Deposits pay for the winning shares, the rest is refunded
+    assert_eq!(balance(&app, &bidder1), 3_000_000);
+    assert_eq!(balance(&app, &bidder2), 0);
+    assert_eq!(balance(&app, &bidder3), 15_000);
+
+    // The influencer also keeps the slashed half of bidder3's deposit
+    assert_eq!(balance(&app, &influencer), 4_000_000 + 12_000_000 + 15_000);
+}
+
+#[test]
+fn test_sealed_bids_with_uniform_price() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) =
+        setup_sealed_auction(&mut app, &vault, &InstantiateMsg::default(), Some(true));
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+    let outsider = app.api().addr_make("outsider");
+
+    app.send_tokens(vault.clone(), outsider.clone(), &coins(1_000, DENOM))
+        .unwrap();
+
+    let err = app
+        .execute_contract(
+            outsider.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CommitBid {
+                stock_id: 1,
+                commitment: "not a hash".to_string(),
+            },
+            &coins(1_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Commitment must be a hex encoded sha256 hash"));
+
+    let bid1 = commit_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bidder1,
+        (10, 500_000, "a"),
+        5_000_000,
+    );
+    let bid2 = commit_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bidder2,
+        (20, 500_000, "b"),
+        5_000_000,
+    );
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * HOUR + 1);
+    });
+
+    assert_eq!(
+        reveal_bid(&mut app, &contract_addr, &bidder1, bid1, 10, 500_000, "a"),
+        ""
+    );
+
+    // The deposit has to cover the revealed bid, bidder2 can't reveal theirs
+    let err = reveal_bid(&mut app, &contract_addr, &bidder2, bid2, 20, 500_000, "b");
+    assert!(err.contains("Deposit of 5000000 doesn't cover the bid's 10000000"));
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(HOUR);
+    });
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::EndAuction { stock_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // Undersubscribed, so bidder1's is the lowest winning bid and sets the price
+    assert_eq!(get_shares(&app, &contract_addr, &bidder1), 500_000);
+    assert_eq!(get_shares(&app, &contract_addr, &influencer), 500_000);
+    assert_eq!(balance(&app, &bidder1), 0);
+
+    // Without a slash the unrevealed deposit is refunded in full
+    assert_eq!(balance(&app, &bidder2), 5_000_000);
+    assert_eq!(balance(&app, &influencer), 5_000_000);
+}
+
+#[test]
+fn test_equal_sealed_bids_go_to_the_earliest_commitment() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) =
+        setup_sealed_auction(&mut app, &vault, &InstantiateMsg::default(), None);
+
+    let bidders = ["bidder1", "bidder2", "bidder3"].map(|name| app.api().addr_make(name));
+
+    let bid_ids = bidders.clone().map(|bidder| {
+        commit_bid(
+            &mut app,
+            &vault,
+            &contract_addr,
+            &bidder,
+            (10, 500_000, "a"),
+            5_000_000,
+        )
+    });
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * HOUR + 1);
+    });
+
+    // Revealing later doesn't cost a bid its place
+    for (bidder, bid_id) in bidders.iter().zip(bid_ids).rev() {
+        assert_eq!(
+            reveal_bid(&mut app, &contract_addr, bidder, bid_id, 10, 500_000, "a"),
+            ""
+        );
+    }
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(HOUR);
+    });
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::EndAuction { stock_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(get_shares(&app, &contract_addr, &bidders[0]), 500_000);
+    assert_eq!(get_shares(&app, &contract_addr, &bidders[1]), 500_000);
+
+    // The last to commit gets the deposit back
+    assert_eq!(balance(&app, &bidders[0]), 0);
+    assert_eq!(balance(&app, &bidders[1]), 0);
+    assert_eq!(balance(&app, &bidders[2]), 5_000_000);
+}
+
+#[test]
+fn test_sealed_bids_can_be_revealed_during_a_halt() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, _) =
+        setup_sealed_auction(&mut app, &vault, &InstantiateMsg::default(), None);
+
+    let bidder = app.api().addr_make("bidder");
+
+    let bid_id = commit_bid(
+        &mut app,
+        &vault,
+        &contract_addr,
+        &bidder,
+        (10, 500_000, "a"),
+        5_000_000,
+    );
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Halt {
+            stock_id: Some(1),
+            reason: "Investigating".to_owned(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * HOUR + 1);
+    });
+
+    // The reveal phase doesn't wait for the halt to be lifted
+    assert_eq!(
+        reveal_bid(&mut app, &contract_addr, &bidder, bid_id, 10, 500_000, "a"),
+        ""
+    );
+
+    let res: GetSealedBidResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetSealedBid { bid_id })
+        .unwrap();
+
+    assert!(res.sealed_bid.revealed);
+}
+
+#[test]
+fn test_sealed_bids_are_allocated_in_batches() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, _) =
+        setup_sealed_auction(&mut app, &vault, &InstantiateMsg::default(), None);
+
+    let bidders =
+        ["bidder1", "bidder2", "bidder3", "bidder4"].map(|name| app.api().addr_make(name));
+    let bids = [(10, 400_000), (20, 400_000), (30, 400_000), (5, 100_000)];
+
+    let bid_ids = bidders
+        .iter()
+        .zip(bids)
+        .map(|(bidder, (price, shares))| {
+            commit_bid(
+                &mut app,
+                &vault,
+                &contract_addr,
+                bidder,
+                (price, shares, "a"),
+                price * shares as u128,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * HOUR + 1);
+    });
+
+    for ((bidder, bid_id), (price, shares)) in bidders.iter().zip(bid_ids).zip(bids) {
+        assert_eq!(
+            reveal_bid(&mut app, &contract_addr, bidder, bid_id, price, shares, "a"),
+            ""
+        );
+    }
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(HOUR);
+    });
+
+    let settle = |app: &mut App, limit: Option<u32>| -> (String, String) {
+        let res = app
+            .execute_contract(
+                bidders[0].clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::SettleAuction { stock_id: 1, limit },
+                &[],
+            )
+            .unwrap();
+
+        let attr = |key: &str| {
+            res.custom_attrs(1)
+                .iter()
+                .find(|attr| attr.key == key)
+                .unwrap()
+                .value
+                .clone()
+        };
+
+        (attr("allocated_bids"), attr("settled_bids"))
+    };
+
+    // One bid at a time from the highest price down, nothing is settled meanwhile
+    assert_eq!(
+        settle(&mut app, Some(1)),
+        ("1".to_string(), "0".to_string())
+    );
+    assert_eq!(
+        settle(&mut app, Some(1)),
+        ("2".to_string(), "0".to_string())
+    );
+
+    // bidder1 takes the last 200_000 shares, which leaves none for bidder4
+    assert_eq!(
+        settle(&mut app, Some(1)),
+        ("3".to_string(), "0".to_string())
+    );
+    assert_eq!(settle(&mut app, None), ("3".to_string(), "5".to_string()));
+
+    assert_eq!(get_shares(&app, &contract_addr, &bidders[2]), 400_000);
+    assert_eq!(get_shares(&app, &contract_addr, &bidders[1]), 400_000);
+    assert_eq!(get_shares(&app, &contract_addr, &bidders[0]), 200_000);
+    assert_eq!(get_shares(&app, &contract_addr, &bidders[3]), 0);
+
+    assert_eq!(balance(&app, &bidders[0]), 2_000_000);
+    assert_eq!(balance(&app, &bidders[3]), 500_000);
+}
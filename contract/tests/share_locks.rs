@@ -0,0 +1,158 @@
+use common::{setup_app, setup_stock_in_sale};
+use cosmwasm_std::coins;
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetSharesResponse, QueryMsg},
+};
+
+mod common;
+
+#[test]
+fn test_sell_orders_cannot_exceed_available_shares() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, bidder1, _) = setup_stock_in_sale(&mut app, &vault);
+
+    // bidder1 holds 50_000 shares, lock 30_000 of them
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 30_000,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetSharesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSharesByOwner {
+                owner: bidder1.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.shares[0].no_of_shares, 50_000);
+    assert_eq!(res.shares[0].locked_shares, 30_000);
+    assert_eq!(res.shares[0].available_shares(), 20_000);
+
+    // A second order for more than what is left is rejected
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateSellOrder {
+                stock_id,
+                price_per_share: 25,
+                shares: 30_000,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Insufficient shares"));
+
+    // Quick sell can't use the locked shares either
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::QuickSell {
+                stock_id,
+                shares: 25_000,
+                price_per_share: 20,
+                slippage: 0,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Insufficient shares"));
+}
+
+#[test]
+fn test_cancel_and_fill_release_locked_shares() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, _, bidder1, _) = setup_stock_in_sale(&mut app, &vault);
+
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 30_000,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // A buyer takes 10_000 of the locked shares
+    let buyer = app.api().addr_make("buyer");
+    app.send_tokens(vault.clone(), buyer.clone(), &coins(200_000, DENOM))
+        .unwrap();
+
+    app.execute_contract(
+        buyer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateBuyOrder {
+            stock_id,
+            price_per_share: 20,
+            shares: 10_000,
+        },
+        &coins(200_000, DENOM),
+    )
+    .unwrap();
+
+    let res: GetSharesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSharesByOwner {
+                owner: bidder1.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.shares[0].no_of_shares, 40_000);
+    assert_eq!(res.shares[0].locked_shares, 20_000);
+
+    // Cancelling releases the rest
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelSellOrder { sell_order_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetSharesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSharesByOwner {
+                owner: bidder1.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.shares[0].no_of_shares, 40_000);
+    assert_eq!(res.shares[0].locked_shares, 0);
+
+    // All 40_000 can now be offered again
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id,
+            price_per_share: 25,
+            shares: 40_000,
+        },
+        &[],
+    )
+    .unwrap();
+}
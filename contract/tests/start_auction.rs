@@ -1,7 +1,13 @@
 use common::{contract_code, setup_app};
-use cosmwasm_std::Addr;
-use cw_multi_test::Executor;
-use influencer_stocks::msg::{ExecuteMsg, GetStockByIdResponse, InstantiateMsg, QueryMsg};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{
+        ExecuteMsg, GetMinimumBidPriceResponse, GetStockByIdResponse, GetStocksResponse,
+        InstantiateMsg, QueryMsg,
+    },
+};
 
 mod common;
 
@@ -15,7 +21,7 @@ fn test_start_auction_success() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -28,6 +34,8 @@ fn test_start_auction_success() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -46,7 +54,20 @@ fn test_start_auction_success() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
 
     let res = app
         .execute_contract(
@@ -89,7 +110,7 @@ fn test_start_auction_unauthorized() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -102,6 +123,8 @@ fn test_start_auction_unauthorized() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -122,7 +145,20 @@ fn test_start_auction_unauthorized() {
     // Try to start auction with different influencer
     let different_influencer = Addr::unchecked("influencer2");
 
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
 
     // Should fail with unauthorized error
     let err = app
@@ -147,7 +183,7 @@ fn test_start_auction_already_active() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -160,6 +196,8 @@ fn test_start_auction_already_active() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -178,7 +216,20 @@ fn test_start_auction_already_active() {
         .unwrap();
 
     // Start auction first time
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -213,7 +264,7 @@ fn test_start_auction_nonexistent_stock() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -226,6 +277,17 @@ fn test_start_auction_nonexistent_stock() {
     let nonexistent_stock_id = 999;
     let start_auction_msg = ExecuteMsg::StartAuction {
         stock_id: nonexistent_stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
     };
 
     let err = app
@@ -253,7 +315,7 @@ fn test_start_auction_after_end() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -266,6 +328,8 @@ fn test_start_auction_after_end() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -284,7 +348,20 @@ fn test_start_auction_after_end() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -324,7 +401,7 @@ fn test_auction_duration() {
         .instantiate_contract(
             code_id,
             vault.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Influencer Stocks",
             None,
@@ -337,6 +414,8 @@ fn test_auction_duration() {
     let ticker = "INF1_1";
     let create_msg = ExecuteMsg::CreateStock {
         ticker: ticker.to_string(),
+        total_shares: None,
+        metadata: None,
     };
 
     let res = app
@@ -355,7 +434,20 @@ fn test_auction_duration() {
         .unwrap();
 
     // Start auction
-    let start_auction_msg = ExecuteMsg::StartAuction { stock_id };
+    let start_auction_msg = ExecuteMsg::StartAuction {
+        stock_id,
+        start_at: None,
+        duration: None,
+        reserve_price: None,
+        uniform_price: None,
+        dutch_auction: None,
+        sealed_bid: None,
+        funding_goal: None,
+        max_shares_per_address: None,
+        min_shares_per_bid: None,
+        presale: None,
+        retained_allocation: None,
+    };
     app.execute_contract(
         influencer.clone(),
         contract_addr.clone(),
@@ -377,3 +469,260 @@ fn test_auction_duration() {
     // Verify auction duration is 24 hours (in milliseconds)
     assert_eq!(end - start, 24 * 60 * 60 * 1000);
 }
+
+const HOUR: u64 = 60 * 60 * 1000;
+
+fn scheduled_stocks(app: &App, contract_addr: &Addr, scheduled: bool) -> Vec<u64> {
+    let res: GetStocksResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetAllStocks {
+                start_after: None,
+                in_auction: None,
+                in_sale: None,
+                marked_as_active_auction: None,
+                scheduled: Some(scheduled),
+            },
+        )
+        .unwrap();
+
+    res.stocks.iter().map(|stock| stock.id).collect()
+}
+
+#[test]
+fn test_scheduled_auction() {
+    let (mut app, vault) = setup_app();
+
+    // Auctions may last between 1 hour and 7 days
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg {
+                min_auction_duration: Some(HOUR),
+                max_auction_duration: Some(7 * 24 * HOUR),
+                ..InstantiateMsg::default()
+            },
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer = Addr::unchecked("influencer1");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "INF1_1".to_string(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let now = app.block_info().time.nanos() / 1_000_000;
+
+    // Durations out of bounds and starts in the past are rejected
+    for (start_at, duration) in [(None, Some(HOUR - 1)), (Some(now - 1), None)] {
+        app.execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::StartAuction {
+                stock_id: 1,
+                start_at,
+                duration,
+                reserve_price: None,
+                uniform_price: None,
+                dutch_auction: None,
+                sealed_bid: None,
+                funding_goal: None,
+                max_shares_per_address: None,
+                min_shares_per_bid: None,
+                presale: None,
+                retained_allocation: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    // Schedule a 2 hour auction an hour from now
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: Some(now + HOUR),
+            duration: Some(2 * HOUR),
+            reserve_price: None,
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.auction_start, Some(now + HOUR));
+    assert_eq!(res.stock.auction_end, Some(now + 3 * HOUR));
+
+    assert_eq!(scheduled_stocks(&app, &contract_addr, true), vec![1]);
+
+    // Bids wait for the start
+    let bidder = Addr::unchecked("bidder");
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(2_000, DENOM))
+        .unwrap();
+
+    let bid_msg = ExecuteMsg::PlaceBid {
+        stock_id: 1,
+        price_per_share: 1,
+        shares: 1_000,
+    };
+
+    let err = app
+        .execute_contract(
+            bidder.clone(),
+            contract_addr.clone(),
+            &bid_msg,
+            &coins(1_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains(&format!("Auction starts at {}", now + HOUR)));
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(60 * 60);
+    });
+
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &bid_msg,
+        &coins(1_000, DENOM),
+    )
+    .unwrap();
+
+    assert!(scheduled_stocks(&app, &contract_addr, true).is_empty());
+    assert_eq!(scheduled_stocks(&app, &contract_addr, false), vec![1]);
+}
+
+#[test]
+fn test_reserve_price() {
+    let (mut app, vault) = setup_app();
+
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg::default(),
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer = Addr::unchecked("influencer1");
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "INF1_1".to_string(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::StartAuction {
+            stock_id: 1,
+            start_at: None,
+            duration: None,
+            reserve_price: Some(5),
+            uniform_price: None,
+            dutch_auction: None,
+            sealed_bid: None,
+            funding_goal: None,
+            max_shares_per_address: None,
+            min_shares_per_bid: None,
+            presale: None,
+            retained_allocation: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetMinimumBidPriceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetMinimumBidPrice {
+                stock_id: 1,
+                shares_requested: 1_000,
+                bidder: None,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.min_price, "5");
+
+    let bidder = Addr::unchecked("bidder");
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(10_000, DENOM))
+        .unwrap();
+
+    let err = app
+        .execute_contract(
+            bidder.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::PlaceBid {
+                stock_id: 1,
+                price_per_share: 4,
+                shares: 1_000,
+            },
+            &coins(4_000, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Bid price is below the reserve price of 5"));
+
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::PlaceBid {
+            stock_id: 1,
+            price_per_share: 5,
+            shares: 1_000,
+        },
+        &coins(5_000, DENOM),
+    )
+    .unwrap();
+}
@@ -0,0 +1,204 @@
+use common::{instantiate, setup_app};
+use cw_multi_test::Executor;
+use influencer_stocks::{
+    msg::{ExecuteMsg, GetStockByIdResponse, GetStocksResponse, InstantiateMsg, QueryMsg},
+    state::{SocialHandle, StockMetadata},
+};
+
+mod common;
+
+fn metadata() -> StockMetadata {
+    StockMetadata {
+        display_name: Some("Influencer One".to_string()),
+        description: Some("Daily vlogs".to_string()),
+        image_uri: Some(
+            "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+        ),
+        socials: vec![SocialHandle {
+            platform: "x".to_string(),
+            handle: "@influencer1".to_string(),
+        }],
+        tags: vec!["lifestyle".to_string(), "travel".to_string()],
+    }
+}
+
+#[test]
+fn test_create_stock_with_metadata() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate(&mut app, &vault, &InstantiateMsg::default());
+
+    let influencer = app.api().addr_make("influencer");
+
+    // Limits are enforced
+    let too_long = StockMetadata {
+        description: Some("a".repeat(1_001)),
+        ..metadata()
+    };
+
+    let bad_uri = StockMetadata {
+        image_uri: Some("ftp://image.png".to_string()),
+        ..metadata()
+    };
+
+    let too_many_tags = StockMetadata {
+        tags: (0..11).map(|i| format!("tag{i}")).collect(),
+        ..metadata()
+    };
+
+    for metadata in [too_long, bad_uri, too_many_tags] {
+        app.execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateStock {
+                ticker: "INFL".to_string(),
+                total_shares: None,
+                metadata: Some(metadata),
+            },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    let res = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateStock {
+                ticker: "INFL".to_string(),
+                total_shares: None,
+                metadata: Some(metadata()),
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert!(res
+        .events
+        .iter()
+        .any(|event| event.ty == "wasm-stock_metadata"));
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.metadata, metadata());
+
+    let res: GetStocksResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetAllStocks {
+                start_after: None,
+                in_auction: None,
+                in_sale: None,
+                marked_as_active_auction: None,
+                scheduled: None,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.stocks[0].metadata, metadata());
+}
+
+#[test]
+fn test_update_stock_metadata() {
+    let (mut app, vault) = setup_app();
+    let contract_addr = instantiate(&mut app, &vault, &InstantiateMsg::default());
+
+    let influencer = app.api().addr_make("influencer");
+
+    // Metadata is optional
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: "INFL".to_string(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.metadata, StockMetadata::default());
+
+    // Only the influencer edits it
+    let err = app
+        .execute_contract(
+            app.api().addr_make("random"),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateStockMetadata {
+                stock_id: 1,
+                metadata: metadata(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    let err = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateStockMetadata {
+                stock_id: 1,
+                metadata: StockMetadata {
+                    display_name: Some(" ".to_string()),
+                    ..metadata()
+                },
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Display name cannot be empty"));
+
+    let res = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateStockMetadata {
+                stock_id: 1,
+                metadata: metadata(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let event = res
+        .events
+        .iter()
+        .find(|event| event.ty == "wasm-stock_metadata")
+        .unwrap();
+
+    assert!(event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "display_name" && attr.value == "Influencer One"));
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.metadata, metadata());
+}
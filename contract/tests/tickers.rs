@@ -0,0 +1,239 @@
+use common::{contract_code, setup_app, setup_stock_in_sale};
+use cosmwasm_std::Addr;
+use cw_multi_test::{error::AnyResult, App, Executor};
+use influencer_stocks::msg::{
+    ExecuteMsg, GetReservedTickersResponse, GetStockByTickerResponse, InstantiateMsg, QueryMsg,
+};
+
+mod common;
+
+fn create_stock(
+    app: &mut App,
+    contract_addr: &Addr,
+    influencer: &Addr,
+    ticker: &str,
+) -> AnyResult<u64> {
+    let res = app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateStock {
+            ticker: ticker.to_string(),
+            total_shares: None,
+            metadata: None,
+        },
+        &[],
+    )?;
+
+    Ok(res
+        .custom_attrs(1)
+        .iter()
+        .find(|attr| attr.key == "stock_id")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap())
+}
+
+#[test]
+fn test_tickers_are_normalized_and_unique() {
+    let (mut app, vault) = setup_app();
+
+    let code_id = app.store_code(contract_code());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            vault.clone(),
+            &InstantiateMsg::default(),
+            &[],
+            "Influencer Stocks",
+            None,
+        )
+        .unwrap();
+
+    let influencer1 = app.api().addr_make("influencer1");
+    let influencer2 = app.api().addr_make("influencer2");
+
+    // Tickers are stored uppercase
+    let stock_id = create_stock(&mut app, &contract_addr, &influencer1, " elon ").unwrap();
+
+    let res: GetStockByTickerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockByTicker {
+                ticker: "Elon".to_string(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.id, stock_id);
+    assert_eq!(res.stock.ticker, "ELON");
+
+    // Nobody else can use it, whatever the case
+    let err = create_stock(&mut app, &contract_addr, &influencer2, "ELON").unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Ticker ELON is already used by stock 1"));
+
+    // Badly formed tickers are rejected
+    for ticker in ["", "E", "THIS_IS_TOO_LONG", "1ELON", "EL-ON", "ÉLON"] {
+        create_stock(&mut app, &contract_addr, &influencer2, ticker).unwrap_err();
+    }
+
+    create_stock(&mut app, &contract_addr, &influencer2, "ELON_2").unwrap();
+
+    // Unknown tickers aren't found
+    app.wrap()
+        .query_wasm_smart::<GetStockByTickerResponse>(
+            contract_addr.clone(),
+            &QueryMsg::GetStockByTicker {
+                ticker: "NOPE".to_string(),
+            },
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn test_reserve_and_reassign_tickers() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, stock_id, influencer, bidder1, _) = setup_stock_in_sale(&mut app, &vault);
+
+    let brand = app.api().addr_make("brand");
+
+    // Only the owner reserves tickers
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::ReserveTicker {
+            ticker: "BANK".to_string(),
+            holder: None,
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::ReserveTicker {
+            ticker: "bank".to_string(),
+            holder: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let err = create_stock(&mut app, &contract_addr, &influencer, "BANK").unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Ticker BANK is reserved"));
+
+    // A listed stock keeps its ticker
+    let err = app
+        .execute_contract(
+            vault.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::ReserveTicker {
+                ticker: "TEST".to_string(),
+                holder: Some(brand.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err.root_cause().to_string().contains("still listed"));
+
+    // Delisting stops trading
+    app.execute_contract(
+        bidder1.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::DelistStock { stock_id },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::DelistStock { stock_id },
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            bidder1.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateSellOrder {
+                stock_id,
+                price_per_share: 20,
+                shares: 10_000,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains(&format!("Stock {stock_id} is delisted")));
+
+    // The delisted stock's ticker can now be reassigned
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::ReserveTicker {
+            ticker: "TEST".to_string(),
+            holder: Some(brand.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: GetReservedTickersResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetReservedTickers {})
+        .unwrap();
+
+    assert_eq!(res.tickers.len(), 2);
+    assert_eq!(res.tickers[1].ticker, "TEST");
+    assert_eq!(res.tickers[1].holder, Some(brand.clone()));
+
+    create_stock(&mut app, &contract_addr, &influencer, "TEST").unwrap_err();
+    let new_stock_id = create_stock(&mut app, &contract_addr, &brand, "TEST").unwrap();
+
+    let res: GetStockByTickerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockByTicker {
+                ticker: "TEST".to_string(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.id, new_stock_id);
+    assert_eq!(res.stock.influencer, brand);
+
+    // Released tickers are open to anyone
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::ReleaseTicker {
+            ticker: "BANK".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    create_stock(&mut app, &contract_addr, &influencer, "BANK").unwrap();
+
+    let res: GetReservedTickersResponse = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetReservedTickers {})
+        .unwrap();
+
+    assert!(res.tickers.is_empty());
+}
@@ -0,0 +1,191 @@
+use common::{balance, place_bid, setup_app, setup_stock, start_auction, AuctionParams};
+use cosmwasm_std::Addr;
+use cw_multi_test::{App, Executor};
+use influencer_stocks::msg::{
+    ExecuteMsg, GetSalesResponse, GetShareResponse, GetStockByIdResponse, InstantiateMsg, QueryMsg,
+};
+
+mod common;
+
+// Start a uniform price auction with the given reserve
+fn setup_uniform_auction(app: &mut App, vault: &Addr, reserve_price: Option<u128>) -> (Addr, Addr) {
+    let (contract_addr, influencer) = setup_stock(app, vault, &InstantiateMsg::default());
+
+    let res = start_auction(
+        app,
+        &contract_addr,
+        &influencer,
+        AuctionParams {
+            reserve_price,
+            uniform_price: Some(true),
+            ..AuctionParams::default()
+        },
+    );
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "uniform_price" && attr.value == "true"));
+
+    (contract_addr, influencer)
+}
+
+fn settle_auction(app: &mut App, contract_addr: &Addr, sender: &Addr) {
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * 60 * 60 + 1);
+    });
+
+    app.execute_contract(
+        sender.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::SettleAuction {
+            stock_id: 1,
+            limit: None,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn clearing_price(app: &App, contract_addr: &Addr) -> Option<u128> {
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    res.stock.clearing_price
+}
+
+#[test]
+fn test_winners_pay_the_clearing_price() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_uniform_auction(&mut app, &vault, None);
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    // Together the bids cover the whole supply, the lowest of them sets the price
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 10, 500_000).unwrap();
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 20, 500_000).unwrap();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(24 * 60 * 60 + 1);
+    });
+
+    let res = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::EndAuction { stock_id: 1 },
+            &[],
+        )
+        .unwrap();
+
+    let attrs = res.custom_attrs(1);
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "proceeds" && attr.value == "10000000"));
+    assert!(attrs
+        .iter()
+        .any(|attr| attr.key == "refunds" && attr.value == "5000000"));
+
+    let res: GetStockByIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetStockById { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert_eq!(res.stock.clearing_price, Some(10));
+
+    // bidder2 gets back what they bid above the clearing price
+    assert_eq!(balance(&app, &bidder1), 0);
+    assert_eq!(balance(&app, &bidder2), 5_000_000);
+    assert_eq!(balance(&app, &influencer), 10_000_000);
+
+    let res: GetSalesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetSalesByStock { stock_id: 1 },
+        )
+        .unwrap();
+
+    assert!(res
+        .sales
+        .iter()
+        .filter(|sale| sale.to != influencer)
+        .all(|sale| sale.price_per_share == 10));
+
+    for bidder in [&bidder1, &bidder2] {
+        let res: GetShareResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetShare {
+                    stock_id: 1,
+                    owner: bidder.clone(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(res.share.no_of_shares, 500_000);
+    }
+}
+
+#[test]
+fn test_undersubscribed_auction_clears_at_the_lowest_bid() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_uniform_auction(&mut app, &vault, Some(5));
+
+    let bidder = app.api().addr_make("bidder");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder, 8, 100_000).unwrap();
+
+    settle_auction(&mut app, &contract_addr, &bidder);
+
+    // The influencer's opening bid for the rest sets no price
+    assert_eq!(clearing_price(&app, &contract_addr), Some(8));
+
+    assert_eq!(balance(&app, &bidder), 0);
+    assert_eq!(balance(&app, &influencer), 800_000);
+
+    // The influencer keeps the shares that weren't bid for
+    let res: GetShareResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetShare {
+                stock_id: 1,
+                owner: influencer.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.share.no_of_shares, 900_000);
+}
+
+#[test]
+fn test_undersubscribed_auction_without_reserve_clears_above_zero() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_uniform_auction(&mut app, &vault, None);
+
+    let bidder1 = app.api().addr_make("bidder1");
+    let bidder2 = app.api().addr_make("bidder2");
+
+    place_bid(&mut app, &vault, &contract_addr, &bidder1, 7, 100_000).unwrap();
+    place_bid(&mut app, &vault, &contract_addr, &bidder2, 9, 100_000).unwrap();
+
+    settle_auction(&mut app, &contract_addr, &bidder1);
+
+    assert_eq!(clearing_price(&app, &contract_addr), Some(7));
+
+    // bidder2 is refunded what it bid above the clearing price
+    assert_eq!(balance(&app, &bidder1), 0);
+    assert_eq!(balance(&app, &bidder2), 200_000);
+    assert_eq!(balance(&app, &influencer), 1_400_000);
+}
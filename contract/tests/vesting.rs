@@ -0,0 +1,250 @@
+use common::{setup_app, setup_stock, start_auction, AuctionParams};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{error::AnyResult, App, AppResponse, Executor};
+use influencer_stocks::{
+    contract::DENOM,
+    msg::{ExecuteMsg, GetShareResponse, GetVestingResponse, InstantiateMsg, QueryMsg},
+    state::RetainedAllocation,
+};
+
+mod common;
+
+const DAY: u64 = 24 * 60 * 60;
+
+// Start an auction in which the influencer retains 20% of the shares,
+// vesting with a 1 day cliff over 4 days
+fn setup_retained_auction(app: &mut App, vault: &Addr) -> (Addr, Addr) {
+    let (contract_addr, influencer) = setup_stock(app, vault, &InstantiateMsg::default());
+
+    let res = start_auction(
+        app,
+        &contract_addr,
+        &influencer,
+        AuctionParams {
+            retained_allocation: Some(RetainedAllocation {
+                retained_bps: 2_000,
+                cliff: DAY * 1000,
+                vesting_duration: 4 * DAY * 1000,
+            }),
+            ..AuctionParams::default()
+        },
+    );
+
+    assert!(res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "retained_shares" && attr.value == "200000"));
+
+    (contract_addr, influencer)
+}
+
+// Bid for every auctioned share and settle the auction
+fn sell_out_auction(app: &mut App, vault: &Addr, contract_addr: &Addr, influencer: &Addr) {
+    let bidder = app.api().addr_make("bidder");
+
+    app.send_tokens(vault.clone(), bidder.clone(), &coins(1_000_000, DENOM))
+        .unwrap();
+
+    // The retained shares aren't up for auction
+    let err = app
+        .execute_contract(
+            bidder.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::PlaceBid {
+                stock_id: 1,
+                price_per_share: 1,
+                shares: 800_001,
+            },
+            &coins(800_001, DENOM),
+        )
+        .unwrap_err();
+
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Cannot bid for more than the 800000 shares of stock 1"));
+
+    app.execute_contract(
+        bidder.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::PlaceBid {
+            stock_id: 1,
+            price_per_share: 1,
+            shares: 800_000,
+        },
+        &coins(800_000, DENOM),
+    )
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(DAY + 1);
+    });
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::EndAuction { stock_id: 1 },
+        &[],
+    )
+    .unwrap();
+}
+
+fn get_vesting(app: &App, contract_addr: &Addr) -> GetVestingResponse {
+    app.wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetVesting { stock_id: 1 })
+        .unwrap()
+}
+
+fn claim_vested(app: &mut App, contract_addr: &Addr, influencer: &Addr) -> AnyResult<AppResponse> {
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::ClaimVested { stock_id: 1 },
+        &[],
+    )
+}
+
+#[test]
+fn test_retained_shares_vest_after_cliff() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_retained_auction(&mut app, &vault);
+
+    sell_out_auction(&mut app, &vault, &contract_addr, &influencer);
+
+    let res = get_vesting(&app, &contract_addr);
+    assert_eq!(res.vesting.total_shares, 200_000);
+    assert_eq!(res.vesting.beneficiary, influencer);
+    assert_eq!((res.vested, res.claimable, res.locked), (0, 0, 200_000));
+
+    // Nothing is released before the cliff
+    let err = claim_vested(&mut app, &contract_addr, &influencer).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("No vested shares to claim"));
+
+    // Only the influencer can claim
+    let err = app
+        .execute_contract(
+            app.api().addr_make("bidder"),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimVested { stock_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+
+    // Halfway through the vesting duration
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(2 * DAY);
+    });
+
+    let res = get_vesting(&app, &contract_addr);
+    assert_eq!(
+        (res.vested, res.claimable, res.locked),
+        (100_000, 100_000, 100_000)
+    );
+
+    let res = claim_vested(&mut app, &contract_addr, &influencer).unwrap();
+    assert!(res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "claimed_shares" && attr.value == "100000"));
+
+    let res = get_vesting(&app, &contract_addr);
+    assert_eq!(
+        (res.vested, res.claimable, res.locked),
+        (100_000, 0, 100_000)
+    );
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(2 * DAY);
+    });
+
+    // Claiming trades nothing, so a halt doesn't hold it up
+    app.execute_contract(
+        vault.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Halt {
+            stock_id: Some(1),
+            reason: "Investigating".to_owned(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res = claim_vested(&mut app, &contract_addr, &influencer).unwrap();
+    assert!(res
+        .custom_attrs(1)
+        .iter()
+        .any(|attr| attr.key == "claimed_shares" && attr.value == "100000"));
+
+    let res: GetShareResponse = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetShare {
+                stock_id: 1,
+                owner: influencer.clone(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(res.share.no_of_shares, 200_000);
+}
+
+#[test]
+fn test_locked_shares_cannot_be_sold() {
+    let (mut app, vault) = setup_app();
+    let (contract_addr, influencer) = setup_retained_auction(&mut app, &vault);
+
+    sell_out_auction(&mut app, &vault, &contract_addr, &influencer);
+
+    let err = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateSellOrder {
+                stock_id: 1,
+                price_per_share: 2,
+                shares: 10_000,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Insufficient shares"));
+
+    let err = app
+        .execute_contract(
+            influencer.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::QuickSell {
+                stock_id: 1,
+                shares: 10_000,
+                price_per_share: 1,
+                slippage: 1,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Insufficient shares"));
+
+    // Once vested and claimed the shares can be sold
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(DAY);
+    });
+
+    claim_vested(&mut app, &contract_addr, &influencer).unwrap();
+
+    app.execute_contract(
+        influencer.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateSellOrder {
+            stock_id: 1,
+            price_per_share: 2,
+            shares: 10_000,
+        },
+        &[],
+    )
+    .unwrap();
+}
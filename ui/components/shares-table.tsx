@@ -41,7 +41,7 @@ export function SharesTable({ shares }: { shares: Share[] }) {
             </TableRow>
           ) : (
             shares.map((share) => (
-              <TableRow key={share.id}>
+              <TableRow key={share.stock_id}>
                 <TableCell>{share.stock_id}</TableCell>
                 <TableCell>{share.ticker}</TableCell>
                 <TableCell className="text-right">
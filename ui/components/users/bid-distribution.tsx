@@ -1,7 +1,13 @@
 import { Bid } from "@/lib/contract/Contract.types";
 import { useMemo } from "react";
 
-export function BidDistribution({ bids: _bids }: { bids: Bid[] }) {
+export function BidDistribution({
+  bids: _bids,
+  totalShares,
+}: {
+  bids: Bid[];
+  totalShares: number;
+}) {
   // sort by no_of_shares
   const bids = useMemo(
     () => _bids.sort((a, b) => b.remaining_shares - a.remaining_shares),
@@ -28,7 +34,9 @@ export function BidDistribution({ bids: _bids }: { bids: Bid[] }) {
           </p>
           <div className="border-zinc-400 w-full border rounded-2xl h-[0.75rem] mb-2 bg-zinc-100">
             <div
-              style={{ width: `${(bid.remaining_shares / 1_000_000) * 100}%` }}
+              style={{
+                width: `${(bid.remaining_shares / totalShares) * 100}%`,
+              }}
               className="h-full bg-zinc-700 rounded-2xl"
             ></div>
           </div>
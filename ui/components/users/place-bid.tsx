@@ -131,7 +131,10 @@ export function PlaceBid() {
         <div className="grid md:grid-cols-2 gap-8 mt-6">
           {stock.marked_as_active_auction && <BidForm stockId={+stockId} />}
 
-          <BidDistribution bids={openBids} />
+          <BidDistribution
+            bids={openBids}
+            totalShares={stock.total_shares}
+          />
         </div>
       </div>
     );
@@ -6,7 +6,7 @@
 
 import { CosmWasmClient, SigningCosmWasmClient, ExecuteResult } from "@cosmjs/cosmwasm-stargate";
 import { Coin, StdFee } from "@cosmjs/amino";
-import { InstantiateMsg, ExecuteMsg, QueryMsg, Addr, OrderSort, GetStocksResponse, Stock, GetBidByIdResponse, Bid, GetBidsResponse, GetBuyOrderByIdResponse, BuyOrder, GetBuyPriceResponse, GetMinimumBidPriceResponse, GetSellPriceResponse, GetBuyOrdersResponse, GetSaleByIdResponse, Sale, GetSalesResponse, GetSalesByUserResponse, GetSellOrderByIdResponse, SellOrder, GetShareByIdResponse, Share, GetSharesResponse, GetStockByIdResponse, GetTotalBuyVolumeResponse, GetTotalSellVolumeResponse } from "./Contract.types";
+import { InstantiateMsg, ExecuteMsg, QueryMsg, Addr, OrderSort, MigrateMsg, GetStocksResponse, Stock, GetBidByIdResponse, Bid, GetBidsResponse, GetBuyOrderByIdResponse, BuyOrder, GetBuyOrderEscrowResponse, GetBuyPriceResponse, GetEscrowBalanceResponse, GetMinimumBidPriceResponse, GetSellPriceResponse, GetBuyOrdersResponse, GetSaleByIdResponse, Sale, GetSalesResponse, GetSalesByUserResponse, GetSellOrderByIdResponse, SellOrder, GetShareResponse, Share, GetSharesResponse, GetStockByIdResponse, GetTotalBuyVolumeResponse, GetTotalSellVolumeResponse } from "./Contract.types";
 export interface ContractReadOnlyInterface {
   contractAddress: string;
   getStockById: ({
@@ -75,11 +75,13 @@ export interface ContractReadOnlyInterface {
   }: {
     owner: Addr;
   }) => Promise<GetSharesResponse>;
-  getShareById: ({
-    shareId
+  getShare: ({
+    owner,
+    stockId
   }: {
-    shareId: number;
-  }) => Promise<GetShareByIdResponse>;
+    owner: Addr;
+    stockId: number;
+  }) => Promise<GetShareResponse>;
   getSellPrice: ({
     requestedShares,
     stockId
@@ -157,6 +159,12 @@ export interface ContractReadOnlyInterface {
   }: {
     user: Addr;
   }) => Promise<GetSalesByUserResponse>;
+  getEscrowBalance: () => Promise<GetEscrowBalanceResponse>;
+  getBuyOrderEscrow: ({
+    buyOrderId
+  }: {
+    buyOrderId: number;
+  }) => Promise<GetBuyOrderEscrowResponse>;
 }
 export class ContractQueryClient implements ContractReadOnlyInterface {
   client: CosmWasmClient;
@@ -174,7 +182,7 @@ export class ContractQueryClient implements ContractReadOnlyInterface {
     this.getMinimumBidPrice = this.getMinimumBidPrice.bind(this);
     this.getSharesByStock = this.getSharesByStock.bind(this);
     this.getSharesByOwner = this.getSharesByOwner.bind(this);
-    this.getShareById = this.getShareById.bind(this);
+    this.getShare = this.getShare.bind(this);
     this.getSellPrice = this.getSellPrice.bind(this);
     this.getBuyPrice = this.getBuyPrice.bind(this);
     this.getOpenBuyOrdersByStock = this.getOpenBuyOrdersByStock.bind(this);
@@ -188,6 +196,8 @@ export class ContractQueryClient implements ContractReadOnlyInterface {
     this.getSalesByStock = this.getSalesByStock.bind(this);
     this.getSalesById = this.getSalesById.bind(this);
     this.getSalesByUser = this.getSalesByUser.bind(this);
+    this.getEscrowBalance = this.getEscrowBalance.bind(this);
+    this.getBuyOrderEscrow = this.getBuyOrderEscrow.bind(this);
   }
   getStockById = async ({
     stockId
@@ -323,14 +333,17 @@ export class ContractQueryClient implements ContractReadOnlyInterface {
       }
     });
   };
-  getShareById = async ({
-    shareId
+  getShare = async ({
+    owner,
+    stockId
   }: {
-    shareId: number;
-  }): Promise<GetShareByIdResponse> => {
+    owner: Addr;
+    stockId: number;
+  }): Promise<GetShareResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
-      get_share_by_id: {
-        share_id: shareId
+      get_share: {
+        owner,
+        stock_id: stockId
       }
     });
   };
@@ -495,6 +508,22 @@ export class ContractQueryClient implements ContractReadOnlyInterface {
       }
     });
   };
+  getEscrowBalance = async (): Promise<GetEscrowBalanceResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      get_escrow_balance: {}
+    });
+  };
+  getBuyOrderEscrow = async ({
+    buyOrderId
+  }: {
+    buyOrderId: number;
+  }): Promise<GetBuyOrderEscrowResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      get_buy_order_escrow: {
+        buy_order_id: buyOrderId
+      }
+    });
+  };
 }
 export interface ContractInterface extends ContractReadOnlyInterface {
   contractAddress: string;
@@ -8,7 +8,7 @@ import { Coin } from "@cosmjs/amino";
 import { MsgExecuteContractEncodeObject } from "@cosmjs/cosmwasm-stargate";
 import { MsgExecuteContract } from "cosmjs-types/cosmwasm/wasm/v1/tx";
 import { toUtf8 } from "@cosmjs/encoding";
-import { InstantiateMsg, ExecuteMsg, QueryMsg, Addr, OrderSort, GetStocksResponse, Stock, GetBidByIdResponse, Bid, GetBidsResponse, GetBuyOrderByIdResponse, BuyOrder, GetBuyPriceResponse, GetMinimumBidPriceResponse, GetSellPriceResponse, GetBuyOrdersResponse, GetSaleByIdResponse, Sale, GetSalesResponse, GetSalesByUserResponse, GetSellOrderByIdResponse, SellOrder, GetShareByIdResponse, Share, GetSharesResponse, GetStockByIdResponse, GetTotalBuyVolumeResponse, GetTotalSellVolumeResponse } from "./Contract.types";
+import { InstantiateMsg, ExecuteMsg, QueryMsg, Addr, OrderSort, MigrateMsg, GetStocksResponse, Stock, GetBidByIdResponse, Bid, GetBidsResponse, GetBuyOrderByIdResponse, BuyOrder, GetBuyOrderEscrowResponse, GetBuyPriceResponse, GetEscrowBalanceResponse, GetMinimumBidPriceResponse, GetSellPriceResponse, GetBuyOrdersResponse, GetSaleByIdResponse, Sale, GetSalesResponse, GetSalesByUserResponse, GetSellOrderByIdResponse, SellOrder, GetShareResponse, Share, GetSharesResponse, GetStockByIdResponse, GetTotalBuyVolumeResponse, GetTotalSellVolumeResponse } from "./Contract.types";
 export interface ContractMsg {
   contractAddress: string;
   sender: string;
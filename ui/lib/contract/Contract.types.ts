@@ -106,8 +106,9 @@ export type QueryMsg = {
     owner: Addr;
   };
 } | {
-  get_share_by_id: {
-    share_id: number;
+  get_share: {
+    owner: Addr;
+    stock_id: number;
   };
 } | {
   get_sell_price: {
@@ -167,9 +168,16 @@ export type QueryMsg = {
   get_sales_by_user: {
     user: Addr;
   };
+} | {
+  get_escrow_balance: {};
+} | {
+  get_buy_order_escrow: {
+    buy_order_id: number;
+  };
 };
 export type Addr = string;
 export type OrderSort = "price_asc" | "price_desc" | "created_at_asc" | "created_at_desc";
+export interface MigrateMsg {}
 export interface GetStocksResponse {
   stocks: Stock[];
 }
@@ -192,7 +200,7 @@ export interface Bid {
   bidder: Addr;
   created_at: number;
   id: number;
-  open: number;
+  open: boolean;
   price_per_share: number;
   remaining_shares: number;
   shares_requested: number;
@@ -216,11 +224,20 @@ export interface BuyOrder {
   stock_id: number;
   [k: string]: unknown;
 }
+export interface GetBuyOrderEscrowResponse {
+  amount: string;
+  buy_order_id: number;
+}
 export interface GetBuyPriceResponse {
   price_per_share: string;
   requested_shares: number;
   total_price: string;
 }
+export interface GetEscrowBalanceResponse {
+  contract_balance: string;
+  is_solvent: boolean;
+  total_escrow: string;
+}
 export interface GetMinimumBidPriceResponse {
   min_price: string;
   shares_requested: number;
@@ -267,11 +284,11 @@ export interface SellOrder {
   stock_id: number;
   [k: string]: unknown;
 }
-export interface GetShareByIdResponse {
+export interface GetShareResponse {
   share: Share;
 }
 export interface Share {
-  id: number;
+  locked_shares?: number;
   no_of_shares: number;
   owner: Addr;
   stock_id: number;
@@ -2,7 +2,6 @@ import { ContractClient } from "./contract/Contract.client";
 import { getStockById, getStockValue } from "./stocks";
 
 export type Share = {
-  id: number;
   stock_id: number;
   ticker: string;
   no_of_shares: number;
@@ -23,7 +22,6 @@ export async function getSharesbyOwner(contractClient: ContractClient) {
     const ticker = (await getStockById(contractClient, share.stock_id)).ticker;
 
     const formatted_shares: Share = {
-      id: share.id,
       stock_id: share.stock_id,
       no_of_shares: share.no_of_shares,
       value_per_share: value.toFixed(6),